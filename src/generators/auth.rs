@@ -0,0 +1,123 @@
+use crate::generators::prelude::*;
+use git2::{Cred, CredentialType, FetchOptions, RemoteCallbacks};
+use std::cell::Cell;
+
+///The struct for the git authentication used when cloning and fetching repositories.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq)]
+pub struct GitAuth {
+    ///The authentication method.
+    #[serde(default)]
+    pub method: GitAuthMethod,
+    ///The username. Defaults to the username in the url, or "git" for ssh and "x-access-token" for tokens.
+    #[serde(default)]
+    pub username: String,
+    ///The private key file used by the ssh_key method.
+    #[serde(default)]
+    pub ssh_key: String,
+    ///The public key file used by the ssh_key method.
+    #[serde(default)]
+    pub ssh_public_key: String,
+    ///The environment variable that holds the passphrase of the private key.
+    #[serde(default)]
+    pub passphrase_env: String,
+    ///The environment variable that holds the token used by the token method.
+    #[serde(default)]
+    pub token_env: String,
+}
+
+///The authentication methods.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GitAuthMethod {
+    ///Use the default behaviour of libgit2 without credentials.
+    #[default]
+    None,
+    ///Use the keys loaded in the ssh agent.
+    SshAgent,
+    ///Use a private key file.
+    SshKey,
+    ///Use a https token read from an environment variable.
+    Token,
+    ///Use the git credential helper configured in the git config.
+    CredentialHelper,
+}
+
+impl GitAuth {
+    ///Validates the authentication such as:
+    /// - the ssh_key method must declare the private key file.
+    /// - the token method must declare the environment variable of the token.
+    pub fn validate(&self, owner: &str) {
+        if self.method == GitAuthMethod::SshKey && self.ssh_key.is_empty() {
+            panic!("The ssh_key authentication of {} must declare the ssh_key file.",owner);
+        }
+        if self.method == GitAuthMethod::Token && self.token_env.is_empty() {
+            panic!("The token authentication of {} must declare the token_env environment variable.",owner);
+        }
+    }
+
+    ///Returns the credentials for a url based on the authentication method.
+    pub fn credentials(&self, url: &str, username_from_url: Option<&str>, allowed_types: CredentialType) -> Result<Cred, git2::Error> {
+        let username: &str = match (self.username.is_empty(), username_from_url) {
+            (false, _) => &self.username,
+            (true, Some(username)) => username,
+            (true, None) => if self.method == GitAuthMethod::Token { "x-access-token" } else { "git" }
+        };
+        if allowed_types.contains(CredentialType::USERNAME) {
+            return Cred::username(username);
+        }
+        match self.method {
+            GitAuthMethod::None => Err(git2::Error::from_str("No authentication method configured.")),
+            GitAuthMethod::SshAgent => Cred::ssh_key_from_agent(username),
+            GitAuthMethod::SshKey => {
+                let passphrase: Option<String> = match self.passphrase_env.is_empty() {
+                    true => None,
+                    false => Some(GitAuth::read_env(&self.passphrase_env)?)
+                };
+                let public_key: Option<PathBuf> = match self.ssh_public_key.is_empty() {
+                    true => None,
+                    false => Some(expand_home(&self.ssh_public_key))
+                };
+                Cred::ssh_key(username,public_key.as_deref(),&expand_home(&self.ssh_key),passphrase.as_deref())
+            }
+            GitAuthMethod::Token => Cred::userpass_plaintext(username,&GitAuth::read_env(&self.token_env)?),
+            GitAuthMethod::CredentialHelper => Cred::credential_helper(&git2::Config::open_default()?,url,username_from_url)
+        }
+    }
+
+    ///Creates the remote callbacks that provide the credentials.
+    ///The credentials are only tried once so a rejected credential fails instead of retrying forever.
+    pub fn remote_callbacks(&self) -> RemoteCallbacks<'_> {
+        let mut callbacks: RemoteCallbacks = RemoteCallbacks::new();
+        if self.method != GitAuthMethod::None {
+            let attempts: Cell<u32> = Cell::new(0);
+            callbacks.credentials(move |url, username_from_url, allowed_types| {
+                attempts.set(attempts.get() + 1);
+                if attempts.get() > 2 {
+                    return Err(git2::Error::from_str(&format!("Authentication failed for {}.",url)));
+                }
+                self.credentials(url,username_from_url,allowed_types)
+            });
+        }
+        callbacks
+    }
+
+    ///Creates the fetch options with the remote callbacks.
+    pub fn fetch_options(&self) -> FetchOptions<'_> {
+        let mut fetch_options: FetchOptions = FetchOptions::new();
+        fetch_options.remote_callbacks(self.remote_callbacks());
+        fetch_options
+    }
+
+    ///Reads an environment variable that holds a secret.
+    fn read_env(name: &str) -> Result<String, git2::Error> {
+        std::env::var(name).map_err(|_| git2::Error::from_str(&format!("The environment variable {} is not set.",name)))
+    }
+}
+
+///Expands a leading ~ to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => PathBuf::from(home).join(rest),
+        _ => PathBuf::from(path)
+    }
+}
@@ -9,12 +9,31 @@ pub struct Compose {
     #[serde(skip_serializing_if = "is_compose_volumes_empty")]
     pub volumes: HashMap<String,Volume>,
     #[serde(default)]
+    #[serde(skip_serializing_if = "is_compose_sources_empty")]
+    pub secrets: HashMap<String,Secret>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_compose_sources_empty")]
+    pub configs: HashMap<String,ComposeConfig>,
+    #[serde(default)]
     #[serde(skip)]
     pub executable: String,
     #[serde(skip)]
     pub file: String,
     #[serde(skip)]
     pub detached: bool,
+    #[serde(skip)]
+    pub wait: bool,
+    #[serde(skip)]
+    pub wait_timeout: u64,
+    ///The directory where the compose file and the env files are written.
+    #[serde(skip)]
+    pub deploy_dir: String,
+    ///Where the env files of the services are written and how they are referred to.
+    #[serde(skip)]
+    pub env_file_layout: EnvFileLayout,
+    ///The project env file passed to the runtime with `--env-file`, empty if there is none.
+    #[serde(skip)]
+    pub env_file: String,
 }
 
 ///Struct for volume under service.
@@ -25,15 +44,37 @@ pub struct ServiceVolume {
     pub source: String,
     pub target: String,
     #[serde(default)]
-    pub read_only: bool
+    pub read_only: bool,
+    ///The options of a bind mount such as the SELinux relabeling.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bind: Option<BindOptions>,
+}
+
+///Struct for the options of a bind mount.
+#[derive(Deserialize,Serialize, Debug, Clone)]
+pub struct BindOptions {
+    ///Relabels the mount for SELinux: "z" shares it between containers, "Z" makes it private.
+    pub selinux: String,
 }
 
 ///Struct for service
 #[derive(Deserialize,Serialize, Debug, Clone)]
 pub struct Service {
+    ///The key of the service in the compose file. Falls back to the hostname if not set.
+    #[serde(default)]
+    #[serde(skip_serializing)]
+    pub name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
     pub hostname: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
     pub image: String,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub build: Option<Build>,
+    #[serde(default)]
     #[serde(skip_serializing_if = "is_vec_empty")]
     pub ports: Vec<String>,
     #[serde(default)]
@@ -41,6 +82,10 @@ pub struct Service {
     #[serde(default)]
     #[serde(skip_serializing_if = "is_hashmap_empty")]
     pub environment: HashMap<String, String>,
+    ///The variables passed through from the host environment into the environment when the compose file is generated.
+    #[serde(default)]
+    #[serde(skip_serializing)]
+    pub inherit: Vec<InheritVar>,
     #[serde(default)]
     #[serde(skip_serializing_if = "is_vec_empty")]
     pub env_file: Vec<String>,
@@ -51,11 +96,223 @@ pub struct Service {
     #[serde(skip_serializing_if = "is_service_volumes_empty")]
     pub volumes: Vec<ServiceVolume>,
     #[serde(default)]
-    #[serde(skip_serializing_if = "is_vec_empty")]
-    pub depends_on: Vec<String>,    
+    #[serde(skip_serializing_if = "DependsOn::is_empty")]
+    pub depends_on: DependsOn,
     #[serde(default)]
     #[serde(skip_serializing_if = "is_string_empty")]
     pub restart: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_vec_empty")]
+    pub secrets: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_vec_empty")]
+    pub configs: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<ServiceCommand>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entrypoint: Option<ServiceCommand>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub working_dir: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub user: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_hashmap_empty")]
+    pub labels: HashMap<String, String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub healthcheck: Option<Healthcheck>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_vec_empty")]
+    pub extra_hosts: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_vec_empty")]
+    pub dns: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_vec_empty")]
+    pub cap_add: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_vec_empty")]
+    pub cap_drop: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_ulimits_empty")]
+    pub ulimits: HashMap<String, Ulimit>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logging: Option<Logging>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub stop_grace_period: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub container_name: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_vec_empty")]
+    pub expose: Vec<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deploy: Option<Deploy>,
+    ///Any other compose service keys that are not modeled, emitted as is.
+    #[serde(default)]
+    #[serde(flatten)]
+    pub extra: Mapping,
+}
+
+///Struct for building the image of a service.
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+pub struct Build {
+    ///The build context. If a repository is set, it is the path inside the repository.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub context: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub dockerfile: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_hashmap_empty")]
+    pub args: HashMap<String, String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub target: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_vec_empty")]
+    pub cache_from: Vec<String>,
+    ///The name of the repository used as the build context.
+    #[serde(default)]
+    #[serde(skip_serializing)]
+    pub repository: String,
+}
+
+///The services a service depends on, either a list of services or a map of services with conditions.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum DependsOn {
+    Short(Vec<String>),
+    Long(HashMap<String, Dependency>),
+}
+
+///Struct for a dependency with a condition.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Dependency {
+    #[serde(default)]
+    pub condition: DependsOnCondition,
+}
+
+///The condition a dependency must satisfy before the service starts.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DependsOnCondition {
+    #[default]
+    ServiceStarted,
+    ServiceHealthy,
+    ServiceCompletedSuccessfully,
+}
+
+impl Default for DependsOn {
+    fn default() -> Self {
+        DependsOn::Short(Vec::new())
+    }
+}
+
+impl DependsOn {
+    ///Returns the services and their conditions.
+    pub fn dependencies(&self) -> Vec<(String, DependsOnCondition)> {
+        match self {
+            DependsOn::Short(services) => services.iter().map(|s| (s.clone(),DependsOnCondition::ServiceStarted)).collect(),
+            DependsOn::Long(services) => services.iter().map(|(s, d)| (s.clone(),d.condition)).collect()
+        }
+    }
+
+    ///Checks if there are no dependencies.
+    ///Used for #[serde(skip_serializing_if="DependsOn::is_empty")]
+    pub fn is_empty(&self) -> bool {
+        match self {
+            DependsOn::Short(services) => services.is_empty(),
+            DependsOn::Long(services) => services.is_empty()
+        }
+    }
+}
+
+///A command in either shell form (string) or exec form (list).
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ServiceCommand {
+    Shell(String),
+    Exec(Vec<String>),
+}
+
+///Struct for the healthcheck of a service.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Healthcheck {
+    ///The test command example: ["CMD", "pg_isready"] or "pg_isready -U admin".
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test: Option<ServiceCommand>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub interval: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub timeout: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_zero")]
+    pub retries: u32,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub start_period: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_false")]
+    pub disable: bool,
+}
+
+///A ulimit, either a single value or a soft/hard pair.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Ulimit {
+    Single(i64),
+    Range { soft: i64, hard: i64 },
+}
+
+///Struct for the logging of a service.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Logging {
+    pub driver: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_hashmap_empty")]
+    pub options: HashMap<String, String>,
+}
+
+///Struct for the deploy section of a service.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Deploy {
+    pub resources: Resources,
+}
+
+///Struct for the resource constraints of a service.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Resources {
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ResourceSpec>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reservations: Option<ResourceSpec>,
+}
+
+///Struct for the cpus and memory of a resource constraint.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ResourceSpec {
+    ///The number of cpus example: "0.5".
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub cpus: String,
+    ///The amount of memory example: "512M".
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub memory: String,
 }
 
 /// Struct for networks
@@ -86,6 +343,28 @@ pub struct Volume {
     pub labels: HashMap<String, String>
 }
 
+///Struct for secrets and configs, both are read from a file or an environment variable unless they are external.
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+pub struct ComposeSource {
+    pub name: String,
+    ///The file where the secret or config is read from.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub file: String,
+    ///The environment variable where the secret or config is read from.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub environment: String,
+    #[serde(default)]
+    pub external: bool,
+}
+
+///A secret of the compose file.
+pub type Secret = ComposeSource;
+
+///A config of the compose file.
+pub type ComposeConfig = ComposeSource;
+
 impl ServiceVolume {
     /// Create a service volume instance.
     pub fn new(kind: String, source: String, target: String, read_only: bool) -> Self{
@@ -93,80 +372,224 @@ impl ServiceVolume {
             kind: kind,
             source: source,
             target: target,
-            read_only: read_only
+            read_only: read_only,
+            bind: None
+        }
+    }
+}
+
+impl Service {
+    ///Returns the key of the service which is the name, or the hostname if the name is not set.
+    pub fn key(&self) -> &String {
+        if self.name.is_empty() { &self.hostname } else { &self.name }
+    }
+
+    ///Validates the service options such as:
+    /// - durations must use the compose duration format example: 1m30s.
+    /// - a ulimit soft value must not be greater than its hard value.
+    /// - extra_hosts must be in the format host:ip.
+    /// - expose must be a port or a port range.
+    /// - cpus must be a number and memory must be a byte value example: 512M.
+    /// - an image or a build must be declared, and a build must have a context or a repository.
+    pub fn validate(&self) {
+        let name: &String = self.key();
+        if name.is_empty() {
+            panic!("A service must declare a name.");
+        }
+        if self.depends_on.dependencies().iter().any(|(dependency, _)| dependency == name) {
+            panic!("Service named {} must not depend on itself.",name);
+        }
+        for variable in &self.inherit {
+            if !crate::generators::dialect::is_valid_name(&variable.name) {
+                panic!("Inherited variable {:?} of service named {} is not a valid name.",variable.name,name);
+            }
+        }
+        match &self.build {
+            Some(build) => {
+                if build.context.is_empty() && build.repository.is_empty() {
+                    panic!("The build of service named {} must declare a context or a repository.",name);
+                }
+            }
+            None => {
+                if self.image.is_empty() {
+                    panic!("Service named {} must declare an image or a build.",name);
+                }
+            }
+        }
+        if let Some(healthcheck) = &self.healthcheck {
+            if !healthcheck.disable && healthcheck.test.is_none() {
+                panic!("The healthcheck of service named {} must declare a test.",name);
+            }
+            for duration in [&healthcheck.interval, &healthcheck.timeout, &healthcheck.start_period] {
+                if !duration.is_empty() && !is_duration(duration) {
+                    panic!("Invalid healthcheck duration {} of service named {}.",duration,name);
+                }
+            }
+        }
+        if !self.stop_grace_period.is_empty() && !is_duration(&self.stop_grace_period) {
+            panic!("Invalid stop_grace_period {} of service named {}.",self.stop_grace_period,name);
+        }
+        for (ulimit_name, ulimit) in &self.ulimits {
+            if let Ulimit::Range { soft, hard } = ulimit {
+                if soft > hard {
+                    panic!("The ulimit {} of service named {} has a soft value greater than its hard value.",ulimit_name,name);
+                }
+            }
+        }
+        for extra_host in &self.extra_hosts {
+            match extra_host.split_once(':') {
+                Some((host, ip)) if !host.is_empty() && !ip.is_empty() => {}
+                _ => panic!("Invalid extra_hosts entry {} of service named {}. Expected host:ip.",extra_host,name)
+            }
+        }
+        for port in &self.expose {
+            if !is_port_range(port) {
+                panic!("Invalid expose port {} of service named {}.",port,name);
+            }
+        }
+        if let Some(logging) = &self.logging {
+            if logging.driver.is_empty() {
+                panic!("The logging of service named {} must declare a driver.",name);
+            }
+        }
+        if let Some(deploy) = &self.deploy {
+            for spec in [&deploy.resources.limits, &deploy.resources.reservations].into_iter().flatten() {
+                if !spec.cpus.is_empty() && spec.cpus.parse::<f64>().is_err() {
+                    panic!("Invalid cpus {} of service named {}.",spec.cpus,name);
+                }
+                if !spec.memory.is_empty() && !is_byte_value(&spec.memory) {
+                    panic!("Invalid memory {} of service named {}.",spec.memory,name);
+                }
+            }
         }
     }
 }
 
+///Checks if a value is a compose duration example: 10s, 1m30s, 500ms.
+fn is_duration(value: &str) -> bool {
+    let mut rest: &str = value;
+    while !rest.is_empty() {
+        let digits: usize = rest.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(rest.len());
+        if digits == 0 {
+            return false;
+        }
+        rest = &rest[digits..];
+        let unit: usize = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+        if !["us", "ms", "s", "m", "h"].contains(&&rest[..unit]) {
+            return false;
+        }
+        rest = &rest[unit..];
+    }
+    !value.is_empty()
+}
+
+///Checks if a value is a port or a port range with an optional protocol example: 3000, 8000-8010/tcp.
+fn is_port_range(value: &str) -> bool {
+    let ports: &str = match value.split_once('/') {
+        Some((ports, protocol)) => if ["tcp", "udp"].contains(&protocol) { ports } else { return false },
+        None => value
+    };
+    ports.split('-').count() <= 2 && ports.split('-').all(|port| port.parse::<u16>().is_ok())
+}
+
+///Checks if a value is a byte value example: 512M, 1g, 1024.
+fn is_byte_value(value: &str) -> bool {
+    let number: &str = value.trim_end_matches(|c: char| "bBkKmMgG".contains(c));
+    value.len() - number.len() <= 2 && number.parse::<f64>().is_ok()
+}
+
 impl Compose {
+    ///Generate a compose file from the configuration using the repositories already present under the services directory.
+    ///Use `Launcher` to fetch the repositories before generating.
+    pub fn new(config: Config) -> Self {
+        let services_dir: String = format!("{}/{}",config.settings.base_dir,&config.settings.services_dir);
+        let repository_paths: HashMap<String,String> = Repository::local_paths(&config.repositories,&services_dir);
+        Self::generate(&config,&repository_paths)
+    }
+
     ///
-    ///Generate a compose file
+    ///Generate a compose file from the configuration using the resolved path of each repository by name.
+    ///The repositories mounted or built by the services must be present in repository_paths.
+    ///The project env file, if any, is written next to the compose file.
     ///
     /// ```ignore
-    /// use crate::generators::env::{ EnvironmentFile };
-    /// use crate::generators::compose::{ Compose};
-    /// use crate::generators::config::{ Config };
-    ///
-    /// let mut config = Config::load("config-test.toml".to_string());
-    /// let deploy_dir: String = format!("{}/{}",config.settings.base_dir,&config.settings.deploy_dir);
+    /// let config = Config::load("config-test.toml".to_string());
     /// let services_dir: String = format!("{}/{}",config.settings.base_dir,&config.settings.services_dir);
-    /// let compose_file: String = String::from("docker-compose-test.yaml");
     /// config.validate();
-    /// let env_file_paths: Vec<String> = EnvironmentFile::generate(&config.env_files,&deploy_dir);
-    /// assert_eq!(env_file_paths.len()>0,true);
-    /// let compose_file_path : String = Compose::generate(&mut config.services,&config.networks,&config.volumes,&config.repositories, compose_file,&deploy_dir,&services_dir);
-    /// assert_eq!(!compose_file_path.is_empty(),true);
+    /// let repository_paths = Repository::local_paths(&config.repositories,&services_dir);
+    /// let compose: Compose = Compose::generate(&config,&repository_paths);
+    /// assert_eq!(!compose.file.is_empty(),true);
     /// ```
-    pub fn new(mut config: Config) -> Self {
+    pub fn generate(config: &Config, repository_paths: &HashMap<String,String>) -> Self {
         let deploy_dir: String = format!("{}/{}",config.settings.base_dir,&config.settings.deploy_dir);
-        let services_dir: String = format!("{}/{}",config.settings.base_dir,&config.settings.services_dir);
-        Self { 
-            executable: config.settings.compose_executable.clone(), 
-            detached: config.settings.compose_detached, 
-            ..Self::generate(
-                &mut config.services,
-                &config.networks,
-                &config.volumes,
-                &config.repositories, 
-                config.settings.compose_file,
-                &deploy_dir,
-                &services_dir
-            )
-        }
-    }
-    pub fn generate(services: &mut Vec<Service>, networks: &Vec<Network>, volumes: &Vec<Volume>, repositories: &Vec<Repository>,  file_name: String, deploy_dir: &String, services_dir: &String) -> Self {
-        let mut compose = Self::default();
+        let mut compose = Self {
+            executable: config.settings.compose_executable.clone(),
+            detached: config.settings.compose_detached,
+            wait: config.settings.compose_wait,
+            wait_timeout: config.settings.compose_wait_timeout,
+            env_file: match &config.project_env {
+                Some(project_env) => project_env.write_project(&config.env_files,&deploy_dir)
+                    .unwrap_or_else(|error| panic!("Unable to generate project env file: {:?}.",error)),
+                None => String::new()
+            },
+            deploy_dir: deploy_dir.clone(),
+            env_file_layout: config.settings.env_file_layout(),
+            ..Self::default()
+        };
         let mut services_repo_volumes: Vec<(String,ServiceVolume)> = Vec::new();
+        let repository_path = |name: &String, service_name: &String| -> &String {
+            repository_paths.get(name).unwrap_or_else(|| panic!("Repository {} used by service named {} is not present. Fetch the repositories first.",name,service_name))
+        };
         
-        for repo in repositories.into_iter() {
-            if repo.clone {
-                let mount_source: String = Repository::git_clone(&repo.name,&repo.url,&repo.branch,services_dir);
-                let service_name: String = repo.service.clone();
-                if !service_name.is_empty() {
-                    services_repo_volumes.push(
-                        (service_name,ServiceVolume::new(String::from("bind"),mount_source,repo.mount_target.clone(),false))
-                    );
+        for repo in &config.repositories {
+            if repo.is_fetched() {
+                for mount in repo.mounts() {
+                    let mut volume: ServiceVolume = ServiceVolume::new(String::from("bind"),mount.source(repository_path(&repo.name,&mount.service)),mount.target.clone(),mount.read_only);
+                    if !mount.relabel.is_empty() {
+                        volume.bind = Some(BindOptions { selinux: mount.relabel.clone() });
+                    }
+                    services_repo_volumes.push((mount.service,volume));
                 }
             }
         }
-        for service in services {
-            let hostname = service.hostname.clone();
+        for mut service in config.services.clone() {
+            let name = service.key().clone();
             for item in &services_repo_volumes {
-                if item.0 ==  hostname {
+                if item.0 == name {
                     service.volumes.push(item.1.clone());
                 }
             }
-            compose.insert_service(hostname,service.clone());
+            if let Some(build) = service.build.as_mut() {
+                if !build.repository.is_empty() {
+                    let repo_path: &String = repository_path(&build.repository,&name);
+                    build.context = if build.context.is_empty() { repo_path.clone() } else { format!("{}/{}",repo_path,build.context) };
+                }
+                //A local context is relative to the base_dir, not to the deploy_dir, a remote context is kept as it is.
+                else if !build.context.contains("://") && !build.context.starts_with("git@") {
+                    build.context = config.settings.resolve_path(&build.context);
+                }
+            }
+            compose.insert_service(name,service);
         }
-        for network in networks {
+        for network in &config.networks {
             let network_name: String = network.name.clone();
             compose.insert_network(network_name,network.clone());
         }
-        for volume in volumes {
+        for volume in &config.volumes {
             let volume_name: String = volume.name.clone();
             compose.insert_volume(volume_name,volume.clone());
         }
-        match Compose::write(compose.clone(),file_name, &deploy_dir) {
+        //The files of the secrets and configs are relative to the base_dir, not to the deploy_dir.
+        let resolve_file = |source: &ComposeSource| ComposeSource { file: config.settings.resolve_path(&source.file), ..source.clone() };
+        for secret in &config.secrets {
+            let secret_name: String = secret.name.clone();
+            compose.insert_secret(secret_name,resolve_file(secret));
+        }
+        for compose_config in &config.configs {
+            let config_name: String = compose_config.name.clone();
+            compose.insert_config(config_name,resolve_file(compose_config));
+        }
+        match Compose::write(compose.clone(),config.settings.compose_file.clone(), &deploy_dir) {
             Ok(file_path) => {
                 Compose { file: file_path.clone(), ..compose }
             }
@@ -176,7 +599,12 @@ impl Compose {
         }
     }
     pub fn insert_service(&mut self, name: String,mut data: Service) {
-        data.env_file = data.env_file.into_iter().map(|file| format!(".{}.env",file) ).collect::<Vec<String>>().to_vec();
+        for variable in &data.inherit {
+            if let Some(value) = variable.resolve(&format!("service named {}",name)) {
+                data.environment.insert(variable.name.clone(),value);
+            }
+        }
+        data.env_file = data.env_file.iter().map(|file| self.env_file_layout.compose_path(&self.deploy_dir,file)).collect();
         self.services.insert(name,data);
     }
     pub fn insert_network(&mut self, name: String,data: Network){
@@ -186,6 +614,14 @@ impl Compose {
     pub fn insert_volume(&mut self, name: String,data: Volume){
         self.volumes.insert(name,data);
     }
+
+    pub fn insert_secret(&mut self, name: String,data: Secret){
+        self.secrets.insert(name,data);
+    }
+
+    pub fn insert_config(&mut self, name: String,data: ComposeConfig){
+        self.configs.insert(name,data);
+    }
     ///Write the compose file.
     pub fn write(compose: Compose, file_name: String, deploy_dir: &String) -> Result<String,Box<dyn Error>> {
         let compose_file: String = serde_yaml::to_string(&compose)?;
@@ -194,50 +630,95 @@ impl Compose {
         f.write(&compose_file.as_bytes())?;
         Ok(file_path)
     }
+    ///The arguments that select the compose file and the project env file.
+    fn file_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = vec![String::from("-f"),self.file.clone()];
+        if !self.env_file.is_empty() {
+            args.push(String::from("--env-file"));
+            args.push(self.env_file.clone());
+        }
+        args
+    }
+
     ///Execute the compose file.
     pub fn up(&self) -> bool {
         let executable:String = self.executable.clone();
         if "docker-compose".to_string() == executable {                
-            let mut args: Vec<String> = Vec::from([
-                String::from("-f"),
-                self.file.clone(),
-                String::from("up")
-            ]);
+            let mut args: Vec<String> = [self.file_args(),vec![String::from("up")]].concat();
             if self.detached {
                 args.push(String::from("-d"));
             }
             ExecuteCommand::run(String::from("docker-compose"),args);
         }
         else if "podman-compose".to_string() == executable {       
-            let mut args: Vec<String> = Vec::from([
-                String::from("-f"),
-                self.file.clone(),
-                String::from("up")
-            ]);
+            let mut args: Vec<String> = [self.file_args(),vec![String::from("up")]].concat();
             if self.detached {
                 args.push(String::from("-d"));
             }
             ExecuteCommand::run(String::from("podman-compose"),args);
         }
+        if self.detached && self.wait {
+            return self.wait_healthy();
+        }
+        true
+    }
+
+    ///Returns the services that have a healthcheck or that another service waits to become healthy.
+    pub fn health_checked_services(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.services.iter()
+            .filter(|(name, service)| {
+                service.healthcheck.as_ref().is_some_and(|h| !h.disable) ||
+                self.services.values().any(|s| s.depends_on.dependencies().contains(&((*name).clone(),DependsOnCondition::ServiceHealthy)))
+            })
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    ///Polls the container runtime until all health checked services are healthy or the wait timeout is reached.
+    pub fn wait_healthy(&self) -> bool {
+        let runtime: String = match self.executable.as_str() {
+            "podman-compose" => String::from("podman"),
+            _ => String::from("docker")
+        };
+        let started: std::time::Instant = std::time::Instant::now();
+        let mut pending: Vec<String> = self.health_checked_services();
+        while !pending.is_empty() {
+            pending.retain(|name| {
+                let container: String = ExecuteCommand::output(self.executable.clone(),[self.file_args(),vec![String::from("ps"),String::from("-q"),name.clone()]].concat()).unwrap_or_default();
+                let container: &str = container.lines().next().unwrap_or_default();
+                if container.is_empty() {
+                    return true;
+                }
+                let status: String = ExecuteCommand::output(runtime.clone(),vec![String::from("inspect"),String::from("--format"),String::from("{{if .State.Health}}{{.State.Health.Status}}{{else}}none{{end}}"),container.to_string()]).unwrap_or_default();
+                if status == "healthy" || status == "none" {
+                    println!("Service {} is {}.",name,if status == "none" { "running" } else { "healthy" });
+                    return false;
+                }
+                true
+            });
+            if pending.is_empty() {
+                break;
+            }
+            if started.elapsed().as_secs() >= self.wait_timeout {
+                println!("Timed out waiting for services to become healthy: {}.",pending.join(", "));
+                return false;
+            }
+            std::thread::sleep(std::time::Duration::from_secs(2));
+        }
         true
     }
+
     ///Terminates the running compose file.
     pub fn down(&self) -> bool {
         let executable:String = self.executable.clone();
         if "docker-compose".to_string() == executable {                                
-            let args: Vec<String> = Vec::from([
-                String::from("-f"),
-                self.file.clone(),
-                String::from("down")
-            ]);
+            let args: Vec<String> = [self.file_args(),vec![String::from("down")]].concat();
             ExecuteCommand::run(String::from("docker-compose"),args);
         }
         else if "podman-compose".to_string() == executable {       
-            let args: Vec<String> = Vec::from([
-                String::from("-f"),
-                self.file.clone(),
-                String::from("down")
-            ]);
+            let args: Vec<String> = [self.file_args(),vec![String::from("down")]].concat();
             ExecuteCommand::run(String::from("podman-compose"),args);
         }
         true
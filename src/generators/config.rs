@@ -11,78 +11,346 @@ pub struct Config {
     #[serde(default)]
     pub volumes: Vec<Volume>,
     #[serde(default)]
+    pub secrets: Vec<Secret>,
+    #[serde(default)]
+    pub configs: Vec<ComposeConfig>,
+    #[serde(default)]
     pub repositories: Vec<Repository>,
+    ///The project env file written as `.env` next to the compose file and passed to the runtime with `--env-file`.
+    ///Its variables are substituted in the compose file, they are not passed to the services.
+    #[serde(default)]
+    pub project_env: Option<EnvironmentFile>,
 }
 
 impl Config {
-    /// loads the TOML file
+    /// loads the TOML file, then recreates the deploy directory and creates the services directory.
     /// # Example
     /// ```ignore
-    /// let mut config = Config::load("config-test.toml".to_string());
-    /// let deploy_dir: String = format!("{}/{}",config.settings.base_dir,&config.settings.deploy_dir);
-    /// let services_dir: String = format!("{}/{}",config.settings.base_dir,&config.settings.services_dir);
-    /// let compose_file: String = String::from("docker-compose-test.yaml");
+    /// let config = Config::load("config-test.toml".to_string());
     /// config.validate();
-    /// let env_file_paths: Vec<String> = EnvironmentFile::generate(&config.env_files,&deploy_dir);
-    /// assert_eq!(env_file_paths.len()>0,true);
-    /// let compose_file_path : String = Compose::generate(&mut config.services,&config.networks,&config.volumes,&config.repositories, compose_file,&deploy_dir,&services_dir);
-    /// assert_eq!(!compose_file_path.is_empty(),true);
+    /// let mut launcher: Launcher = Launcher::new(config);
+    /// launcher.fetch_repositories();
+    /// let compose: Compose = launcher.generate();
     /// ```
     pub fn load(config_path: String) -> Self {
-        let data = read_to_string(config_path).expect("Unable to load config.toml file.");
-        match toml::from_str::<Config>(data.as_str()) {
-            Ok(config) => {
-                let base_dir: &String = &config.settings.base_dir;
-                let deploy_dir: String = format!("{}/{}",base_dir,&config.settings.deploy_dir);
-
-                match remove_dir_all(&deploy_dir) {
-                    Ok(_) => {
-                        create_dir(&deploy_dir).expect("Unabel to create directory");
-                    },
-                    Err(error) => {
-                        if error.kind() == std::io::ErrorKind::NotFound {
-                            create_dir(&deploy_dir).expect("Unabel to create deploy directory");
-                        }
-                        else{
-                            panic!("Unable to remove deploy dir: {:?}",error);
-                        }
-                    }
-                };
-                let services_dir: String = format!("{}/{}",base_dir,&config.settings.services_dir);
+        let config: Config = Self::read(config_path);
+        let base_dir: &String = &config.settings.base_dir;
+        let deploy_dir: String = format!("{}/{}",base_dir,&config.settings.deploy_dir);
 
-                if Path::new(&services_dir).try_exists().is_err() {
-                    create_dir(&services_dir).expect("Unabel to create services directory");
+        match remove_dir_all(&deploy_dir) {
+            Ok(_) => {
+                create_dir(&deploy_dir).expect("Unabel to create directory");
+            },
+            Err(error) => {
+                if error.kind() == std::io::ErrorKind::NotFound {
+                    create_dir(&deploy_dir).expect("Unabel to create deploy directory");
                 }
+                else{
+                    panic!("Unable to remove deploy dir: {:?}",error);
+                }
+            }
+        };
+        let services_dir: String = format!("{}/{}",base_dir,&config.settings.services_dir);
+
+        if Path::new(&services_dir).try_exists().is_err() {
+            create_dir(&services_dir).expect("Unabel to create services directory");
+        }
+        config
+    }
+
+    /// Reads the TOML file without preparing the deploy and services directories.
+    /// The sources of the environment files are imported.
+    pub fn read(config_path: String) -> Self {
+        let data = read_to_string(config_path).expect("Unable to load config.toml file.");
+        match toml::from_str::<Config>(data.as_str()) {
+            Ok(mut config) => {
+                config.resolve_repository_paths();
+                config.import_env_files();
                 config
             }
             Err(error) => panic!("Unable to parse toml file. {}",error)
         }
     }
 
+    /// Resolves the relative local paths of the repositories against the base_dir.
+    pub fn resolve_repository_paths(&mut self) {
+        for repo in self.repositories.iter_mut() {
+            repo.path = self.settings.resolve_path(&repo.path);
+        }
+    }
+
+    /// Imports the values of the sources of the environment files and of the project env file.
+    /// The project env file is named project_env unless it declares a name.
+    pub fn import_env_files(&mut self) {
+        for env_file in self.env_files.iter_mut() {
+            env_file.import_source(&self.settings.base_dir);
+        }
+        if let Some(project_env) = self.project_env.as_mut() {
+            if project_env.name.is_empty() {
+                project_env.name = String::from("project_env");
+            }
+            project_env.import_source(&self.settings.base_dir);
+        }
+    }
+
     ///Validates the configuration file such as:
+    /// - service names must be unique.
     /// - if a service uses a network it must be declared in the list of networks.
     /// - if a service uses a environment file, it must be declared in the list of environment files
+    /// - the variables of each environment file must be unique. See `EnvironmentFile::validate`.
+    /// - the env file pattern and directory of the settings must be valid. See `EnvFileLayout::validate`.
+    /// - the environment files an environment file extends must be declared and must not extend each other.
+    /// - each environment file must have a name.
+    /// - the project env file must be valid, use the compose dialect and extend only declared environment files.
+    /// - secrets and configs must have exactly one source (file or environment) unless they are external.
+    /// - if a service uses a secret or a config, it must be declared in the list of secrets or configs.
+    /// - if a service depends on another service, it must be declared in the list of services.
+    /// - if a service builds from a repository, it must be declared in the list of repositories and the context must stay inside it.
+    /// - the git authentication of the settings and of each repository must be valid.
+    /// - repository names must be unique and each repository must be valid. See `Repository::validate`.
+    /// - if a repository is mounted into a service, it must be declared in the list of services.
+    /// - the options of each service must be valid and container names must be unique. See `Service::validate`.
+    ///
+    /// Prints the warnings to stderr. See `Config::warnings`.
     pub fn validate(&self) {
         let networks = &self.networks;
         let env_files = &self.env_files;
+        let secrets = &self.secrets;
+        let configs = &self.configs;
+
+        //Validate the git authentication.
+        self.settings.git_auth.validate("settings");
+        for repository in &self.repositories {
+            repository.validate();
+            if let Some(auth) = &repository.auth {
+                auth.validate(&format!("repository {}",repository.name));
+            }
+            if self.repositories.iter().filter(|&r| r.name == repository.name).count() > 1 {
+                panic!("Repository {} is declared more than once.",repository.name);
+            }
+            for mount in repository.mounts() {
+                if !self.services.iter().any(|s| s.key() == &mount.service) {
+                    panic!("Unable to find service {} of repository named {} in the list of services.",mount.service,repository.name);
+                }
+            }
+        }
+
+        //Validate the environment files.
+        self.settings.env_file_layout().validate();
+        for env_file in env_files {
+            if env_file.name.is_empty() {
+                panic!("An environment file in the list of environment files has no name.");
+            }
+            env_file.validate();
+            //Resolving panics on undeclared or circular extends.
+            env_file.resolved_values(env_files);
+        }
+        if let Some(project_env) = &self.project_env {
+            project_env.validate();
+            if project_env.dialect != EnvDialect::Compose {
+                panic!("The project env file {} must use the compose dialect.",project_env.name);
+            }
+            project_env.resolved_values(env_files);
+        }
+
+        //Validate the sources of the secrets and configs.
+        for secret in secrets {
+            Self::validate_source("secret",&secret.name,&secret.file,&secret.environment,secret.external);
+            if secrets.iter().filter(|&s| s.name == secret.name).count() > 1 {
+                panic!("Secret {} is declared more than once.",secret.name);
+            }
+        }
+        for config in configs {
+            Self::validate_source("config",&config.name,&config.file,&config.environment,config.external);
+            if configs.iter().filter(|&c| c.name == config.name).count() > 1 {
+                panic!("Config {} is declared more than once.",config.name);
+            }
+        }
 
         for service in &self.services {
-            let service_hostname: &String = &service.hostname;
+            let service_name: &String = service.key();
             let service_networks: &Vec<String> = &service.networks;
             let service_env_files: &Vec<String> = &service.env_file;
+            service.validate();
+            if self.services.iter().filter(|&s| s.key() == service_name).count() > 1 {
+                panic!("Service named {} is declared more than once.",service_name);
+            }
+            if !service.container_name.is_empty() && self.services.iter().filter(|&s| s.container_name == service.container_name).count() > 1 {
+                panic!("Container name {} of service named {} is used by more than one service.",service.container_name,service_name);
+            }
             
             //Validate the networks declared in a service to the network configurations.
             for service_network in service_networks {
                 if networks.into_iter().find( |&n| &n.name == service_network).is_none() {
-                    panic!("Unable to find network {} of service named {} in the list of networks.",service_network,service_hostname);
+                    panic!("Unable to find network {} of service named {} in the list of networks.",service_network,service_name);
                 }
             }
             //Validate the environment files.
             for service_env_file in service_env_files {
                 if env_files.into_iter().find( |&n| &n.name == service_env_file).is_none() {
-                    panic!("Unable to find environment file {} of service named {} in the list of environment files.",service_env_file,service_hostname);
+                    panic!("Unable to find environment file {} of service named {} in the list of environment files.",service_env_file,service_name);
+                }
+            }
+            //Validate the services the service depends on.
+            for (dependency, _) in service.depends_on.dependencies() {
+                if !self.services.iter().any(|s| s.key() == &dependency) {
+                    panic!("Unable to find service {} that service named {} depends on in the list of services.",dependency,service_name);
+                }
+            }
+            //Validate the repository used as the build context.
+            if let Some(build) = &service.build {
+                if !build.repository.is_empty() && !self.repositories.iter().any(|r| r.name == build.repository) {
+                    panic!("Unable to find repository {} of service named {} in the list of repositories.",build.repository,service_name);
+                }
+                if !build.repository.is_empty() && !Repository::is_inner_path(&build.context) {
+                    panic!("Build context {} of service named {} must be a relative path without '..' inside the repository {}.",build.context,service_name,build.repository);
+                }
+            }
+            //Validate the secrets and configs used by the service.
+            for service_secret in &service.secrets {
+                if !secrets.iter().any(|s| &s.name == service_secret) {
+                    panic!("Unable to find secret {} of service named {} in the list of secrets.",service_secret,service_name);
                 }
             }
+            for service_config in &service.configs {
+                if !configs.iter().any(|c| &c.name == service_config) {
+                    panic!("Unable to find config {} of service named {} in the list of configs.",service_config,service_name);
+                }
+            }
+        }
+
+        for warning in self.warnings() {
+            eprintln!("{}",warning);
         }
     }
-}
\ No newline at end of file
+
+    ///Returns the warnings about the configuration that do not prevent launching it:
+    /// - a service waits for a service to become healthy but that service does not declare a healthcheck.
+    /// - an environment file is not used by any service nor extended by another environment file.
+    /// - a variable of the environment of a service overrides a different value of one of its env files.
+    /// - a service references a ${VAR} without a default that no env file, project env file, environment of the service or host variable defines.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = Vec::new();
+        for service in &self.services {
+            for (dependency, condition) in service.depends_on.dependencies() {
+                if let Some(target) = self.services.iter().find(|s| s.key() == &dependency) {
+                    if condition == DependsOnCondition::ServiceHealthy && target.healthcheck.is_none() {
+                        warnings.push(format!("Service named {} waits for {} to become healthy but it does not declare a healthcheck, the image must provide one.",service.key(),dependency));
+                    }
+                }
+            }
+        }
+
+        for env_file in &self.env_files {
+            let used: bool = self.services.iter().any(|s| s.env_file.contains(&env_file.name))
+                || self.env_files.iter().chain(&self.project_env).any(|f| f.extends.contains(&env_file.name));
+            if !used {
+                warnings.push(format!("Environment file {} is not used by any service.",env_file.name));
+            }
+        }
+
+        for service in &self.services {
+            let mut environment: Vec<(&String,&String)> = service.environment.iter().collect();
+            environment.sort();
+            for file_name in &service.env_file {
+                let Some(env_file) = self.env_files.iter().find(|f| &f.name == file_name) else { continue };
+                for (value, _) in env_file.resolved_values(&self.env_files) {
+                    let overridden = environment.iter().find(|(name, inline)| **name == value.name.to_uppercase() && **inline != value.value);
+                    if let Some((name, inline)) = overridden {
+                        warnings.push(format!("Service named {} sets {} to {:?} in environment which overrides {:?} of env_file {}.",service.key(),name,inline,value.value,file_name));
+                    }
+                }
+            }
+        }
+
+        let defined: Vec<String> = self.env_files.iter().chain(&self.project_env)
+            .flat_map(|f| f.resolved_values(&self.env_files))
+            .map(|(value, _)| value.name.to_uppercase())
+            .collect();
+        for service in &self.services {
+            let mut references: Vec<String> = Vec::new();
+            if let Ok(data) = serde_yaml::to_value(service) {
+                collect_references(&data,&mut references);
+            }
+            for name in references {
+                let inherited: bool = service.inherit.iter().any(|v| v.name == name);
+                if !defined.contains(&name) && !service.environment.contains_key(&name) && !inherited && std::env::var_os(&name).is_none() {
+                    warnings.push(format!("Service named {} references ${{{}}} but no env file, environment or host variable defines it.",service.key(),name));
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Returns the effective environment of a service in compose precedence order:
+    /// the env files in the order they are listed, a later file overriding an earlier one,
+    /// then the environment of the service which overrides the env files, then the host variables the service inherits.
+    /// Each variable has the source of its value.
+    pub fn effective_environment(&self, service_name: &str) -> Vec<EffectiveVar> {
+        let service: &Service = self.services.iter().find(|s| s.key() == service_name)
+            .unwrap_or_else(|| panic!("Unable to find service {} in the list of services.",service_name));
+        let mut variables: Vec<EffectiveVar> = Vec::new();
+        let mut set = |variable: EffectiveVar| {
+            match variables.iter_mut().find(|existing| existing.name == variable.name) {
+                Some(existing) => *existing = variable,
+                None => variables.push(variable)
+            }
+        };
+        for file_name in &service.env_file {
+            let env_file: &EnvironmentFile = self.env_files.iter().find(|f| &f.name == file_name)
+                .unwrap_or_else(|| panic!("Unable to find environment file {} of service named {} in the list of environment files.",file_name,service_name));
+            for (value, declared_in) in env_file.resolved_values(&self.env_files) {
+                set(EffectiveVar {
+                    name: value.name.to_uppercase(),
+                    value: value.value,
+                    source: EnvSource::EnvFile { file: file_name.clone(), declared_in }
+                });
+            }
+        }
+        let mut environment: Vec<(&String,&String)> = service.environment.iter().collect();
+        environment.sort();
+        for (name, value) in environment {
+            set(EffectiveVar { name: name.clone(), value: value.clone(), source: EnvSource::Environment });
+        }
+        for variable in &service.inherit {
+            if let Some(value) = variable.resolve(&format!("service named {}",service_name)) {
+                set(EffectiveVar { name: variable.name.clone(), value, source: EnvSource::Host });
+            }
+        }
+        variables
+    }
+
+    /// Returns the effective environment of a service written in the format, to run the service outside a container
+    /// or to inspect it. See `Config::effective_environment`.
+    pub fn export_environment(&self, service_name: &str, format: ExportFormat) -> String {
+        format.export(&self.effective_environment(service_name))
+    }
+
+    ///Validates that a secret or config declares exactly one source, or none if it is external.
+    fn validate_source(kind: &str, name: &String, file: &String, environment: &String, external: bool) {
+        let sources: usize = [file, environment].iter().filter(|s| !s.is_empty()).count();
+        if external && sources > 0 {
+            panic!("The external {} {} must not declare a file or environment source.",kind,name);
+        }
+        if !external && sources != 1 {
+            panic!("The {} {} must declare exactly one source, either file or environment.",kind,name);
+        }
+    }
+}
+
+///Collects the names of the ${VAR} references without a default in the strings of a value, each name once.
+fn collect_references(data: &serde_yaml::Value, references: &mut Vec<String>) {
+    match data {
+        serde_yaml::Value::String(text) => {
+            for name in crate::generators::dialect::references(text) {
+                if !references.contains(&name) {
+                    references.push(name);
+                }
+            }
+        }
+        serde_yaml::Value::Sequence(items) => items.iter().for_each(|item| collect_references(item,references)),
+        serde_yaml::Value::Mapping(mapping) => mapping.values().for_each(|item| collect_references(item,references)),
+        serde_yaml::Value::Tagged(tagged) => collect_references(&tagged.value,references),
+        _ => {}
+    }
+}
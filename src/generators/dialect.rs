@@ -0,0 +1,220 @@
+use crate::generators::prelude::*;
+
+///The format of a generated environment file, each one quotes and escapes the values for its reader.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum EnvDialect {
+    ///The env_file of docker compose and podman compose. References like ${NAME} are interpolated by the runtime.
+    #[default]
+    Compose,
+    ///A file read with `source` or `.` by a POSIX shell. References like ${NAME} are expanded by the shell.
+    Posix,
+    ///The EnvironmentFile of a systemd unit. Values are never expanded.
+    Systemd,
+}
+
+impl EnvDialect {
+    ///Returns the value quoted and escaped for the dialect, an empty value is left empty.
+    ///In the compose dialect a value with a literal `$` and no ${NAME} reference is single quoted with `'` escaped as `\'`,
+    ///since docker compose and python-dotenv, the reader of podman compose, both keep the rest of a single quoted value
+    ///as it is but disagree on `\$`. Panics if the value cannot be written for both, see `EnvDialect::can_quote`.
+    pub fn quote(&self, value: &str) -> String {
+        if value.is_empty() {
+            return String::new();
+        }
+        if !self.can_quote(value) {
+            panic!("The value {:?} cannot be written in the {:?} dialect, a backslash before a backslash, a quote or the end of a value with a literal $ is read differently by docker compose and podman compose.",value,self);
+        }
+        if is_literal_dollar(self,value) {
+            return format!("'{}'",value.replace('\'',"\\'"));
+        }
+        let mut quoted: String = String::from("\"");
+        let mut rest: &str = value;
+        while let Some(c) = rest.chars().next() {
+            if *self != EnvDialect::Systemd {
+                if let Some(length) = reference_length(rest) {
+                    quoted.push_str(&rest[..length]);
+                    rest = &rest[length..];
+                    continue;
+                }
+            }
+            match (self, c) {
+                (_, '\\') => quoted.push_str("\\\\"),
+                (_, '"') => quoted.push_str("\\\""),
+                (_, '$') => quoted.push_str("\\$"),
+                (EnvDialect::Compose, '\n') => quoted.push_str("\\n"),
+                (EnvDialect::Compose, '\r') => quoted.push_str("\\r"),
+                (EnvDialect::Posix | EnvDialect::Systemd, '`') => quoted.push_str("\\`"),
+                (_, c) => quoted.push(c)
+            }
+            rest = &rest[c.len_utf8()..];
+        }
+        quoted.push('"');
+        quoted
+    }
+
+    ///Checks if the value is read back as it is by every reader of the dialect.
+    ///A compose value with a literal `$` must not have a backslash before a backslash, a quote or the end of the value.
+    pub fn can_quote(&self, value: &str) -> bool {
+        if !is_literal_dollar(self,value) {
+            return true;
+        }
+        let mut chars = value.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\\' && chars.peek().map_or(true, |next| *next == '\\' || *next == '\'') {
+                return false;
+            }
+        }
+        true
+    }
+
+    ///Parses the content of an environment file into its variables in the order they are declared.
+    ///Blank lines, comments and the `export` prefix are skipped. References like ${NAME} are kept as they are.
+    pub fn parse(&self, content: &str) -> Result<Vec<(String,String)>,std::io::Error> {
+        let mut variables: Vec<(String,String)> = Vec::new();
+        let mut chars = content.chars().peekable();
+        let mut line: usize = 1;
+        let invalid = |message: String| std::io::Error::new(std::io::ErrorKind::InvalidData,message);
+        while let Some(&c) = chars.peek() {
+            if c == '\n' {
+                line += 1;
+                chars.next();
+                continue;
+            }
+            if c.is_whitespace() {
+                chars.next();
+                continue;
+            }
+            if c == '#' || (c == ';' && *self == EnvDialect::Systemd) {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+                continue;
+            }
+
+            let mut key: String = String::new();
+            while let Some(c) = chars.next_if(|&c| c != '=' && c != '\n') {
+                key.push(c);
+            }
+            if chars.next() != Some('=') {
+                return Err(invalid(format!("Line {} is not a NAME=value assignment.",line)));
+            }
+            let key: String = key.trim().strip_prefix("export ").unwrap_or(key.trim()).trim().to_string();
+            if !is_valid_name(&key) {
+                return Err(invalid(format!("Line {} has an invalid variable name {:?}.",line,key)));
+            }
+
+            let mut value: String = String::new();
+            let mut quoted: bool = false;
+            while let Some(c) = chars.next_if(|&c| c != '\n') {
+                match c {
+                    '\'' => {
+                        quoted = true;
+                        loop {
+                            match chars.next() {
+                                Some('\'') => break,
+                                Some('\\') if *self == EnvDialect::Compose && chars.peek() == Some(&'\'') => {
+                                    value.push('\'');
+                                    chars.next();
+                                }
+                                Some(c) => {
+                                    line += usize::from(c == '\n');
+                                    value.push(c);
+                                }
+                                None => return Err(invalid(format!("Line {} has an unterminated single quote.",line)))
+                            }
+                        }
+                    }
+                    '"' => {
+                        quoted = true;
+                        loop {
+                            match chars.next() {
+                                Some('"') => break,
+                                Some('\\') => match (self, chars.next()) {
+                                    (EnvDialect::Compose, Some('n')) => value.push('\n'),
+                                    (EnvDialect::Compose, Some('r')) => value.push('\r'),
+                                    (EnvDialect::Compose, Some(c)) => value.push(c),
+                                    (_, Some('\n')) => line += 1,
+                                    (_, Some(c)) if "$`\"\\".contains(c) => value.push(c),
+                                    (_, Some(c)) => {
+                                        value.push('\\');
+                                        value.push(c);
+                                    }
+                                    (_, None) => return Err(invalid(format!("Line {} has an unterminated double quote.",line)))
+                                },
+                                Some(c) => {
+                                    line += usize::from(c == '\n');
+                                    value.push(c);
+                                }
+                                None => return Err(invalid(format!("Line {} has an unterminated double quote.",line)))
+                            }
+                        }
+                    }
+                    '\\' if *self != EnvDialect::Compose => {
+                        if let Some(c) = chars.next_if(|&c| c != '\n') {
+                            value.push(c);
+                        }
+                    }
+                    '#' if value.is_empty() || value.ends_with(char::is_whitespace) => {
+                        while chars.next_if(|&c| c != '\n').is_some() {}
+                    }
+                    c => value.push(c)
+                }
+            }
+            if !quoted {
+                value = value.trim().to_string();
+            }
+            variables.push((key,value));
+        }
+        Ok(variables)
+    }
+}
+
+///Checks if the name is a portable variable name, a letter or an underscore followed by letters, digits or underscores.
+pub fn is_valid_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+///Returns the names of the ${NAME} references without a default in the text. A `$$` is an escaped `$`.
+pub fn references(text: &str) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+    let mut index: usize = 0;
+    while let Some(offset) = text[index..].find('$') {
+        let rest: &str = &text[index + offset..];
+        if rest.starts_with("$$") {
+            index += offset + 2;
+            continue;
+        }
+        match reference_length(rest) {
+            Some(length) => {
+                let reference: &str = &rest[2..length - 1];
+                if !reference.contains(":-") {
+                    names.push(reference.to_string());
+                }
+                index += offset + length;
+            }
+            None => index += offset + 1
+        }
+    }
+    names
+}
+
+///Checks if the value is written single quoted, a compose value with a literal `$` and no ${NAME} reference.
+fn is_literal_dollar(dialect: &EnvDialect, value: &str) -> bool {
+    *dialect == EnvDialect::Compose && value.contains('$') && !has_reference(value)
+}
+
+///Checks if the text contains a ${NAME} or ${NAME:-default} reference.
+fn has_reference(text: &str) -> bool {
+    text.char_indices().any(|(index, c)| c == '$' && reference_length(&text[index..]).is_some())
+}
+
+///Returns the length of the ${NAME} or ${NAME:-default} reference at the start of the text.
+pub fn reference_length(text: &str) -> Option<usize> {
+    let inner: &str = text.strip_prefix("${")?;
+    let end: usize = inner.find('}')?;
+    let name: &str = inner[..end].split(":-").next().unwrap_or_default();
+    match is_valid_name(name) {
+        true => Some(end + 3),
+        false => None
+    }
+}
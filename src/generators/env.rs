@@ -1,4 +1,5 @@
 use crate::generators::prelude::*;
+use crate::generators::dialect::is_valid_name;
 
 #[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
 ///The struct for the environment variable.
@@ -6,16 +7,293 @@ pub struct EnvironmentVar {
      /// The variable/key name.
     pub name: String,
      /// The value of the environment variable.
-    pub value: String
+    pub value: String,
+     /// The description written as a comment above the variable.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub description: String,
 }
 
 #[derive(Deserialize,Serialize, Debug, Clone)]
 ///The struct for the environment file
 pub struct EnvironmentFile {
-    /// The name of the env file.
+    /// The name of the env file. Optional for the project env file.
+    #[serde(default)]
     pub name: String,
     /// The list of key/value pairs of the env file.
-    pub values: Vec<EnvironmentVar>
+    /// Also accepts a table of names to strings, integers, floats or booleans, example: { DB_PORT = 5432 }.
+    #[serde(default)]
+    #[serde(deserialize_with = "deserialize_values")]
+    pub values: Vec<EnvironmentVar>,
+    /// An existing env file whose values are imported, absolute or relative to the base_dir. The values override them.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_string_empty")]
+    pub source: String,
+    /// The values read from the source. See `EnvironmentFile::import_source`.
+    #[serde(skip)]
+    pub imported: Vec<EnvironmentVar>,
+    /// The format of the generated file. Default: compose
+    #[serde(default)]
+    pub dialect: EnvDialect,
+    /// The env files whose values are inherited, in order. The values of this file override them.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_vec_empty")]
+    pub extends: Vec<String>,
+    /// The variables passed through from the host environment when the file is generated. They override the values.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "is_inherit_empty")]
+    pub inherit: Vec<InheritVar>,
+}
+
+///A variable passed through from the host environment of the launcher.
+///Declared as the name of a required variable, or as a table: { name = "HTTP_PROXY", required = false, default = "" }.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(from = "InheritSpec")]
+pub struct InheritVar {
+    ///The name of the host variable.
+    pub name: String,
+    ///Generating fails if the host variable is not set and there is no default.
+    pub required: bool,
+    ///The value used when the host variable is not set.
+    pub default: Option<String>,
+}
+
+///The declaration of an inherited variable, a name or a table.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum InheritSpec {
+    Name(String),
+    Table {
+        name: String,
+        #[serde(default = "default_required")]
+        required: bool,
+        #[serde(default)]
+        default: Option<String>,
+    },
+}
+
+fn default_required() -> bool {
+    true
+}
+
+impl From<InheritSpec> for InheritVar {
+    fn from(spec: InheritSpec) -> Self {
+        match spec {
+            InheritSpec::Name(name) => InheritVar { name, required: true, default: None },
+            InheritSpec::Table { name, required, default } => InheritVar { name, required, default },
+        }
+    }
+}
+
+impl InheritVar {
+    ///Returns the value of the host variable, or the default if it is not set.
+    ///Panics if the variable is required and there is neither, the owner names what inherits it.
+    pub fn resolve(&self, owner: &str) -> Option<String> {
+        match std::env::var(&self.name).ok().or_else(|| self.default.clone()) {
+            None if self.required => panic!("Host environment variable {} inherited by {} is not set.",self.name,owner),
+            value => value
+        }
+    }
+}
+
+///The values of an env file, a list of variables or a table of names to values.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EnvironmentValues {
+    List(Vec<EnvironmentVar>),
+    Table(toml::Table),
+}
+
+///Deserializes the values of an env file from a list of variables or a table, keeping the order they are declared in.
+fn deserialize_values<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<EnvironmentVar>, D::Error> {
+    match EnvironmentValues::deserialize(deserializer)? {
+        EnvironmentValues::List(values) => Ok(values),
+        EnvironmentValues::Table(table) => table.into_iter().map(|(name, value)| {
+            let value: String = match value {
+                toml::Value::String(value) => value,
+                toml::Value::Integer(value) => value.to_string(),
+                toml::Value::Float(value) => value.to_string(),
+                toml::Value::Boolean(value) => value.to_string(),
+                value => return Err(serde::de::Error::custom(format!("Environment variable {} must be a string, integer, float or boolean, found {}.",name,value.type_str())))
+            };
+            Ok(EnvironmentVar { name, value, description: String::new() })
+        }).collect()
+    }
+}
+
+///Where the value of a variable of the effective environment of a service comes from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EnvSource {
+    ///The environment of the service.
+    Environment,
+    ///A host variable the service inherits.
+    Host,
+    ///An env_file of the service. The value is declared in the file or in one of the files it extends.
+    EnvFile { file: String, declared_in: String },
+}
+
+///A variable of the effective environment of a service.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EffectiveVar {
+    pub name: String,
+    pub value: String,
+    pub source: EnvSource,
+}
+
+impl std::fmt::Display for EnvSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EnvSource::Environment => write!(f,"environment"),
+            EnvSource::Host => write!(f,"host environment"),
+            EnvSource::EnvFile { file, declared_in } if file == declared_in => write!(f,"env_file {}",file),
+            EnvSource::EnvFile { file, declared_in } => write!(f,"env_file {} from {}",file,declared_in),
+        }
+    }
+}
+
+impl std::fmt::Display for EffectiveVar {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,"{}={} ({})",self.name,self.value,self.source)
+    }
+}
+
+///The format the effective environment of a service is exported in. See `Config::export_environment`.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    ///NAME="value" lines read by compose and most dotenv libraries.
+    #[default]
+    Dotenv,
+    ///export NAME="value" lines read with `source` or `.` by a POSIX shell.
+    Shell,
+    ///A JSON object of names to values in the order of the environment.
+    Json,
+    ///The export lines of an `.envrc` loaded by direnv.
+    Direnv,
+}
+
+impl ExportFormat {
+    ///Returns the variables written in the format, each value quoted and escaped for its reader.
+    pub fn export(&self, variables: &[EffectiveVar]) -> String {
+        match self {
+            ExportFormat::Dotenv => variables.iter()
+                .map(|v| format!("{}={}\n",v.name,EnvDialect::Compose.quote(&v.value)))
+                .collect(),
+            ExportFormat::Shell | ExportFormat::Direnv => variables.iter()
+                .map(|v| format!("export {}={}\n",v.name,EnvDialect::Posix.quote(&v.value)))
+                .collect(),
+            ExportFormat::Json => {
+                let members: Vec<String> = variables.iter()
+                    .map(|v| format!("  {}: {}",json_string(&v.name),json_string(&v.value)))
+                    .collect();
+                match members.is_empty() {
+                    true => String::from("{}\n"),
+                    false => format!("{{\n{}\n}}\n",members.join(",\n"))
+                }
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "dotenv" => Ok(ExportFormat::Dotenv),
+            "shell" => Ok(ExportFormat::Shell),
+            "json" => Ok(ExportFormat::Json),
+            "direnv" => Ok(ExportFormat::Direnv),
+            _ => Err(format!("Unknown export format {}, expected dotenv, shell, json or direnv.",format))
+        }
+    }
+}
+
+///Returns the text as a TOML basic string, quoted and escaped.
+fn toml_string(text: &str) -> String {
+    let mut quoted: String = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 || c == '\u{7f}' => quoted.push_str(&format!("\\u{:04X}",c as u32)),
+            c => quoted.push(c)
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+///Returns the text as a JSON string, quoted and escaped.
+fn json_string(text: &str) -> String {
+    let mut quoted: String = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}",c as u32)),
+            c => quoted.push(c)
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+///Where the env files are written under the deploy directory and how the compose file refers to them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvFileLayout {
+    ///The file name pattern, {name} is replaced by the name of the env file.
+    pub pattern: String,
+    ///The subdirectory of the deploy directory the env files are written to.
+    pub dir: String,
+    ///Refer to the env files with absolute paths in the compose file instead of paths relative to it.
+    pub absolute: bool,
+}
+
+impl Default for EnvFileLayout {
+    fn default() -> Self {
+        Self { pattern: String::from(".{name}.env"), dir: String::new(), absolute: false }
+    }
+}
+
+impl EnvFileLayout {
+    ///Returns the path of an env file relative to the deploy directory.
+    pub fn relative_path(&self, name: &str) -> String {
+        let file_name: String = self.pattern.replace("{name}",name);
+        match self.dir.trim_matches('/') {
+            "" => file_name,
+            dir => format!("{}/{}",dir,file_name)
+        }
+    }
+
+    ///Returns the path an env file is written to.
+    pub fn file_path(&self, deploy_dir: &String, name: &str) -> String {
+        format!("{}/{}",deploy_dir,self.relative_path(name))
+    }
+
+    ///Returns the path of an env file in the compose file, relative to the compose file or absolute.
+    pub fn compose_path(&self, deploy_dir: &String, name: &str) -> String {
+        match self.absolute {
+            true => self.file_path(deploy_dir,name),
+            false => self.relative_path(name)
+        }
+    }
+
+    ///Validates the pattern contains {name} and no '/', and the subdirectory is relative without '..'.
+    pub fn validate(&self) {
+        if !self.pattern.contains("{name}") || self.pattern.contains('/') {
+            panic!("The env file pattern {} must contain {{name}} and no '/'.",self.pattern);
+        }
+        if Path::new(&self.dir).has_root() || Path::new(&self.dir).components().any(|c| c == std::path::Component::ParentDir) {
+            panic!("The env file directory {} must be a relative directory without '..'.",self.dir);
+        }
+    }
 }
 
 impl EnvironmentFile {
@@ -25,14 +303,14 @@ impl EnvironmentFile {
     /// let config = Config::load("config-test-docker.toml".to_string());
     /// let deploy_dir: String = format!("{}/{}",config.settings.base_dir,&config.settings.deploy_dir);
     /// config.validate();
-    /// let env_file_paths: Vec<String> = EnvironmentFile::generate(&config.env_files,&deploy_dir);
+    /// let env_file_paths: Vec<String> = EnvironmentFile::generate(&config.env_files,&deploy_dir,&config.settings.env_file_layout());
     /// ```
     /// 
-    pub fn generate(env_files: &Vec<EnvironmentFile>, deploy_dir: &String) -> Vec<String> {
+    pub fn generate(env_files: &Vec<EnvironmentFile>, deploy_dir: &String, layout: &EnvFileLayout) -> Vec<String> {
         let mut file_paths: Vec<String> = Vec::new();
         for item in env_files {
-            let data = EnvironmentFile { name: item.name.clone(), values: item.values.to_vec() };
-            match EnvironmentFile::write(data,&deploy_dir) {
+            let data = item.resolved(env_files);
+            match EnvironmentFile::write(data,deploy_dir,layout) {
                 Ok(file_path) => {
                     file_paths.push(file_path);
                 }
@@ -44,16 +322,163 @@ impl EnvironmentFile {
         file_paths
     }
 
-    /// Write an environment file then store it under the configured path of deploy_dir.
-    pub fn write(env_file: EnvironmentFile, deploy_dir: &String ) -> Result<String,SerdeEnvFileError> {
-        let mut values: HashMap<String, String> = HashMap::new();
-        let file_name: String  = format!(".{}.env",env_file.name);
-        let file_path: String  = format!("{}/{}",deploy_dir,file_name);
-        let path_buff: PathBuf = PathBuf::from(file_path.clone());
-        for item in env_file.values {
-            values.insert(item.name.clone(),item.value.clone());
+    /// Write an environment file then store it under the configured path of deploy_dir at the path of the layout.
+    /// The variables keep the order they are declared in.
+    pub fn write(env_file: EnvironmentFile, deploy_dir: &String, layout: &EnvFileLayout) -> Result<String,std::io::Error> {
+        let file_path: String  = layout.file_path(deploy_dir,&env_file.name);
+        if let Some(parent) = Path::new(&file_path).parent() {
+            std::fs::create_dir_all(parent)?;
         }
-        serde_envfile::to_file(&path_buff,&values)?;
+        std::fs::write(&file_path,env_file.to_env_string()?)?;
         Ok(file_path)
     }
+
+    /// Write the project env file as `.env` in deploy_dir, next to the compose file, resolving the files it extends.
+    /// Returns the path of the written file.
+    pub fn write_project(&self, env_files: &[EnvironmentFile], deploy_dir: &String) -> Result<String,std::io::Error> {
+        let file_path: String = format!("{}/.env",deploy_dir);
+        std::fs::create_dir_all(deploy_dir)?;
+        std::fs::write(&file_path,self.resolved(env_files).to_env_string()?)?;
+        Ok(file_path)
+    }
+
+    /// Reads an existing env file of the dialect into an environment file with the name.
+    pub fn from_env_file(name: &str, path: &str, dialect: EnvDialect) -> Result<EnvironmentFile,std::io::Error> {
+        let values: Vec<EnvironmentVar> = dialect.parse(&read_to_string(path)?)?.into_iter()
+            .map(|(name, value)| EnvironmentVar { name, value, description: String::new() })
+            .collect();
+        Ok(EnvironmentFile { name: name.to_string(), values, dialect, extends: Vec::new(), inherit: Vec::new(), source: String::new(), imported: Vec::new() })
+    }
+
+    /// Reads the values of the source, a relative source is read from the base_dir.
+    pub fn import_source(&mut self, base_dir: &String) {
+        if self.source.is_empty() {
+            return;
+        }
+        if Path::new(&self.source).is_relative() {
+            self.source = format!("{}/{}",base_dir,self.source);
+        }
+        match EnvironmentFile::from_env_file(&self.name,&self.source,self.dialect) {
+            Ok(env_file) => self.imported = env_file.values,
+            Err(error) => panic!("Unable to import source {} of environment file {}: {}",self.source,self.name,error)
+        }
+    }
+
+    /// Returns the environment file as a [[env_files]] table of the configuration, each string written as a TOML basic string.
+    pub fn to_toml(&self) -> String {
+        let quote = |value: &String| toml_string(value);
+        let mut content: String = format!("[[env_files]]\nname = {}\n",quote(&self.name));
+        if self.dialect != EnvDialect::default() {
+            let dialect: String = toml::Value::try_from(self.dialect).ok().and_then(|v| v.as_str().map(String::from)).unwrap_or_default();
+            content.push_str(&format!("dialect = {}\n",quote(&dialect)));
+        }
+        content.push_str("values = [\n");
+        for item in &self.values {
+            content.push_str(&format!("    {{ name = {}, value = {} }},\n",quote(&item.name),quote(&item.value)));
+        }
+        content.push_str("]\n");
+        content
+    }
+
+    /// Returns the environment file with the values of the files it extends merged in.
+    pub fn resolved(&self, env_files: &[EnvironmentFile]) -> EnvironmentFile {
+        EnvironmentFile {
+            values: self.resolved_values(env_files).into_iter().map(|(value, _)| value).collect(),
+            extends: Vec::new(),
+            inherit: Vec::new(),
+            source: String::new(),
+            imported: Vec::new(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns the values of the files it extends, then the values imported from its source, then its own values,
+    /// then the inherited host variables, with the name of the file, the source path or "host environment" each value comes from.
+    /// Panics if a required host variable is not set.
+    /// A value overrides the value of the same name, in the position that name was first declared.
+    /// Panics if an extended file is not in the list of environment files or if the files extend each other.
+    pub fn resolved_values(&self, env_files: &[EnvironmentFile]) -> Vec<(EnvironmentVar,String)> {
+        self.collect_values(env_files,&mut vec![self.name.clone()])
+    }
+
+    ///Collects the values of the extended files then its own values, the chain is the list of files being extended.
+    fn collect_values(&self, env_files: &[EnvironmentFile], chain: &mut Vec<String>) -> Vec<(EnvironmentVar,String)> {
+        let mut values: Vec<(EnvironmentVar,String)> = Vec::new();
+        for parent_name in &self.extends {
+            if chain.contains(parent_name) {
+                panic!("Environment file {} extends itself through {} -> {}.",parent_name,chain.join(" -> "),parent_name);
+            }
+            let parent: &EnvironmentFile = env_files.iter().find(|f| &f.name == parent_name)
+                .unwrap_or_else(|| panic!("Unable to find environment file {} extended by environment file {} in the list of environment files.",parent_name,self.name));
+            chain.push(parent_name.clone());
+            for (value, declared_in) in parent.collect_values(env_files,chain) {
+                Self::merge_value(&mut values,value,declared_in);
+            }
+            chain.pop();
+        }
+        for value in &self.imported {
+            Self::merge_value(&mut values,value.clone(),self.source.clone());
+        }
+        for value in &self.values {
+            Self::merge_value(&mut values,value.clone(),self.name.clone());
+        }
+        for variable in &self.inherit {
+            if let Some(value) = variable.resolve(&format!("environment file {}",self.name)) {
+                Self::merge_value(&mut values,EnvironmentVar { name: variable.name.clone(), value, description: String::new() },String::from("host environment"));
+            }
+        }
+        values
+    }
+
+    ///Overrides the value of the same name, names are compared upper cased, or appends the value.
+    fn merge_value(values: &mut Vec<(EnvironmentVar,String)>, value: EnvironmentVar, declared_in: String) {
+        match values.iter_mut().find(|(existing, _)| existing.name.to_uppercase() == value.name.to_uppercase()) {
+            Some(existing) => *existing = (value,declared_in),
+            None => values.push((value,declared_in))
+        }
+    }
+
+    /// Returns the content of the environment file. Each description is written as a comment above its variable.
+    /// Names are upper cased and values are quoted and escaped for the dialect, an empty value is left empty.
+    pub fn to_env_string(&self) -> Result<String,std::io::Error> {
+        self.validate_names()?;
+        let mut content: String = String::new();
+        for item in &self.values {
+            for line in item.description.lines() {
+                content.push_str(format!("# {}",line).trim_end());
+                content.push('\n');
+            }
+            content.push_str(&format!("{}={}\n",item.name.to_uppercase(),self.dialect.quote(&item.value)));
+        }
+        Ok(content)
+    }
+
+    /// Validates the environment file, the variable names must be unique and made of letters, digits or underscores.
+    pub fn validate(&self) {
+        if let Err(error) = self.validate_names() {
+            panic!("{}",error);
+        }
+    }
+
+    ///Checks the variable names, names that only differ in case are duplicates because they are upper cased.
+    fn validate_names(&self) -> Result<(),std::io::Error> {
+        let invalid = |message: String| Err(std::io::Error::new(std::io::ErrorKind::InvalidInput,message));
+        for (index, item) in self.values.iter().enumerate() {
+            if !is_valid_name(&item.name) {
+                return invalid(format!("Environment variable {:?} of environment file {} is not a valid name.",item.name,self.name));
+            }
+            if self.values[..index].iter().any(|other| other.name.to_uppercase() == item.name.to_uppercase()) {
+                return invalid(format!("Environment variable {} is declared more than once in environment file {}.",item.name,self.name));
+            }
+            if !self.dialect.can_quote(&item.value) {
+                return invalid(format!("Environment variable {} of environment file {} cannot be written in the {:?} dialect, a backslash before a backslash, a quote or the end of a value with a literal $ is read differently by docker compose and podman compose.",item.name,self.name,self.dialect));
+            }
+        }
+        for variable in &self.inherit {
+            if !is_valid_name(&variable.name) {
+                return invalid(format!("Inherited variable {:?} of environment file {} is not a valid name.",variable.name,self.name));
+            }
+        }
+        Ok(())
+    }
 }
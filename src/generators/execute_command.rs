@@ -29,4 +29,10 @@ impl ExecuteCommand {
             }
         }
     }
+
+    ///Executes a command and returns its trimmed standard output.
+    pub fn output(exec: String, args: Vec<String>) -> Result<String, std::io::Error> {
+        let output = Command::new(&exec).args(args).stderr(Stdio::null()).output()?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
 }
\ No newline at end of file
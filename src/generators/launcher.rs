@@ -0,0 +1,85 @@
+use crate::generators::prelude::*;
+
+///The struct for the launcher. It runs each phase of launching the stack:
+///fetching the repositories, then generating the environment and compose files.
+pub struct Launcher {
+    pub config: Config,
+    ///The resolved path of each repository by name.
+    pub repository_paths: HashMap<String, String>,
+}
+
+impl Launcher {
+    /// Create a launcher for a configuration.
+    /// The repositories already present under the services directory are resolved without fetching.
+    /// # Example
+    /// ```ignore
+    /// let config = Config::load("config-test-docker.toml".to_string());
+    /// config.validate();
+    /// let mut launcher: Launcher = Launcher::new(config);
+    /// launcher.fetch_repositories();
+    /// let compose: Compose = launcher.generate();
+    /// compose.up();
+    /// compose.down();
+    /// ```
+    pub fn new(config: Config) -> Self {
+        let mut launcher: Launcher = Self { config, repository_paths: HashMap::new() };
+        launcher.repository_paths = Repository::local_paths(&launcher.config.repositories,&launcher.services_dir());
+        launcher
+    }
+
+    ///The directory where the generated files are written.
+    pub fn deploy_dir(&self) -> String {
+        format!("{}/{}",self.config.settings.base_dir,self.config.settings.deploy_dir)
+    }
+
+    ///The directory where the repositories are cloned.
+    pub fn services_dir(&self) -> String {
+        format!("{}/{}",self.config.settings.base_dir,self.config.settings.services_dir)
+    }
+
+    ///The lockfile of the repositories.
+    pub fn lock_file(&self) -> String {
+        format!("{}/{}",self.config.settings.base_dir,self.config.settings.lock_file)
+    }
+
+    /// Clone or update the repositories to their locked commits, printing the progress.
+    pub fn fetch_repositories(&mut self) -> Vec<RepositorySyncReport> {
+        self.fetch_repositories_with_progress(false,&ConsoleProgress::default())
+    }
+
+    /// Update the repositories to their latest commits and refresh the lockfile, printing the progress.
+    pub fn update_repositories(&mut self) -> Vec<RepositorySyncReport> {
+        self.fetch_repositories_with_progress(true,&ConsoleProgress::default())
+    }
+
+    /// Clone or update the repositories, reporting the progress to the progress reporter.
+    /// Set update to refresh the lockfile with the latest commits instead of the locked ones.
+    pub fn fetch_repositories_with_progress(&mut self, update: bool, progress: &dyn ProgressReporter) -> Vec<RepositorySyncReport> {
+        let settings: &Settings = &self.config.settings;
+        let reports: Vec<RepositorySyncReport> = Repository::sync_all(
+            &self.config.repositories,
+            &self.services_dir(),
+            &self.lock_file(),
+            update,
+            &settings.git_auth,
+            settings.clone_concurrency,
+            progress
+        );
+        for report in &reports {
+            self.repository_paths.insert(report.name.clone(),report.path.clone());
+        }
+        reports
+    }
+
+    /// Returns the status of the repositories without touching the network.
+    /// Set fetch to fetch origin first so ahead and behind are compared with the latest remote branches.
+    pub fn repository_status(&self, fetch: bool) -> Vec<RepositoryStatus> {
+        Repository::status_all(&self.config.repositories,&self.services_dir(),fetch,&self.config.settings.git_auth)
+    }
+
+    /// Generate the environment files, the project env file and the compose file using the resolved repositories.
+    pub fn generate(&self) -> Compose {
+        EnvironmentFile::generate(&self.config.env_files,&self.deploy_dir(),&self.config.settings.env_file_layout());
+        Compose::generate(&self.config,&self.repository_paths)
+    }
+}
@@ -0,0 +1,63 @@
+use crate::generators::prelude::*;
+
+///The struct for the lockfile which records the resolved commit of each repository.
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
+pub struct Lockfile {
+    #[serde(default)]
+    pub repositories: Vec<LockedRepository>,
+}
+
+///The struct for a locked repository.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct LockedRepository {
+    ///The name of the repository.
+    pub name: String,
+    ///The repository url.
+    pub url: String,
+    ///The configured reference example: branch:main, tag:v1.0.0 or rev:1a2b3c4.
+    pub reference: String,
+    ///The resolved commit.
+    pub commit: String,
+}
+
+impl Lockfile {
+    /// Loads the lockfile, or an empty lockfile if it does not exist yet.
+    pub fn load(path: &String) -> Self {
+        match read_to_string(path) {
+            Ok(data) => {
+                match toml::from_str::<Lockfile>(data.as_str()) {
+                    Ok(lockfile) => lockfile,
+                    Err(error) => panic!("Unable to parse lockfile {}. {}",path,error)
+                }
+            }
+            Err(error) => {
+                if error.kind() == std::io::ErrorKind::NotFound {
+                    Self::default()
+                }
+                else {
+                    panic!("Unable to load lockfile {}: {:?}",path,error);
+                }
+            }
+        }
+    }
+
+    /// Write the lockfile.
+    pub fn write(&self, path: &String) -> Result<(), Box<dyn Error>> {
+        let data: String = toml::to_string(self)?;
+        let mut f = File::create(path)?;
+        f.write_all(data.as_bytes())?;
+        Ok(())
+    }
+
+    /// Finds a locked repository if it still matches the configured url and reference.
+    pub fn find(&self, name: &String, url: &String, reference: &String) -> Option<&LockedRepository> {
+        self.repositories.iter().find(|r| &r.name == name && &r.url == url && &r.reference == reference)
+    }
+
+    /// Records the commit of a repository, replacing the previous entry.
+    pub fn lock(&mut self, locked: LockedRepository) {
+        self.repositories.retain(|r| r.name != locked.name);
+        self.repositories.push(locked);
+        self.repositories.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+}
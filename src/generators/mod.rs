@@ -1,8 +1,13 @@
 pub mod prelude;
 pub mod settings;
 pub mod env;
+pub mod dialect;
 pub mod repository;
+pub mod lockfile;
+pub mod auth;
+pub mod progress;
 pub mod config;
 pub mod compose;
+pub mod launcher;
 pub mod tests;
 pub mod execute_command;
\ No newline at end of file
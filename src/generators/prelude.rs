@@ -1,5 +1,4 @@
 pub use serde::{Deserialize, Serialize};
-pub use serde_envfile::{Error as SerdeEnvFileError};
 pub use serde_yaml::{Mapping, Value, Error as SerdeYamlError};
 pub use std::fs::{read_to_string, remove_dir_all, create_dir, File};
 pub use std::path::{ PathBuf, Path };
@@ -7,10 +6,15 @@ pub use std::collections::HashMap;
 pub use std::error::Error;
 pub use std::io::Write;
 pub use crate::generators::settings::{ Settings };
-pub use crate::generators::env::{ EnvironmentFile, EnvironmentVar };
-pub use crate::generators::compose::{ Compose, ServiceVolume, Service, Volume, Network };
+pub use crate::generators::env::{ EnvironmentFile, EnvironmentVar, EnvSource, EffectiveVar, ExportFormat, EnvFileLayout, InheritVar };
+pub use crate::generators::dialect::EnvDialect;
+pub use crate::generators::compose::{ Compose, ServiceVolume, BindOptions, Service, Volume, Network, ComposeSource, Secret, ComposeConfig, Build, DependsOn, Dependency, DependsOnCondition, ServiceCommand, Healthcheck, Ulimit, Logging, Deploy, Resources, ResourceSpec };
 pub use crate::generators::config::{ Config };
-pub use crate::generators::repository::{ Repository };
+pub use crate::generators::launcher::{ Launcher };
+pub use crate::generators::repository::{ Repository, RepositoryMount, SyncMode, SyncStatus, RepositorySyncReport, RepositoryStatus };
+pub use crate::generators::lockfile::{ Lockfile, LockedRepository };
+pub use crate::generators::auth::{ GitAuth, GitAuthMethod };
+pub use crate::generators::progress::{ ProgressReporter, TransferProgress, NoProgress, ConsoleProgress };
 pub use crate::generators::execute_command::{ ExecuteCommand };
 
 ///Check if a Vector of String is empty.
@@ -42,4 +46,29 @@ pub fn is_service_volumes_empty(n: &Vec<ServiceVolume>) -> bool {
 ///Used for #[serde(skip_serializing_if="is_string_empty")] 
 pub fn is_string_empty(n: &String) -> bool {
     n.is_empty()
+}
+///Check if a Hashmap of String,ComposeSource is empty.
+///Used for #[serde(skip_serializing_if="is_compose_sources_empty")] 
+pub fn is_compose_sources_empty(n: &HashMap<String, ComposeSource>) -> bool {
+    n.is_empty()
+}
+///Check if a Hashmap of String,Ulimit is empty.
+///Used for #[serde(skip_serializing_if="is_ulimits_empty")] 
+pub fn is_ulimits_empty(n: &HashMap<String, Ulimit>) -> bool {
+    n.is_empty()
+}
+///Check if a vector of InheritVar is empty.
+///Used for #[serde(skip_serializing_if="is_inherit_empty")] 
+pub fn is_inherit_empty(n: &[InheritVar]) -> bool {
+    n.is_empty()
+}
+///Checks if a number is zero.
+///Used for #[serde(skip_serializing_if="is_zero")] 
+pub fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+///Checks if a bool is false.
+///Used for #[serde(skip_serializing_if="is_false")] 
+pub fn is_false(n: &bool) -> bool {
+    !*n
 }
\ No newline at end of file
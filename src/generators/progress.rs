@@ -0,0 +1,67 @@
+use crate::generators::prelude::*;
+
+///The transfer progress of a repository while it is cloned or fetched.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TransferProgress {
+    pub received_objects: usize,
+    pub indexed_objects: usize,
+    pub total_objects: usize,
+    pub received_bytes: usize,
+}
+
+///Receives the progress of the repositories while they are synced.
+///The reporter is shared between the threads that sync the repositories.
+pub trait ProgressReporter: Sync {
+    ///Called when a repository starts syncing.
+    fn started(&self, _name: &str) {}
+    ///Called when objects are received while cloning or fetching a repository.
+    fn transfer(&self, _name: &str, _progress: &TransferProgress) {}
+    ///Called when a repository finished syncing.
+    fn finished(&self, _report: &RepositorySyncReport) {}
+}
+
+///A progress reporter that ignores the progress.
+pub struct NoProgress;
+
+impl ProgressReporter for NoProgress {}
+
+///A progress reporter that prints the progress to the console.
+///The received objects of each repository are printed at every tenth of the total, each step once.
+#[derive(Default)]
+pub struct ConsoleProgress {
+    ///The last step printed by repository name.
+    printed_steps: std::sync::Mutex<HashMap<String,usize>>,
+}
+
+impl ConsoleProgress {
+    ///Returns the line to print for the transfer progress of the repository, if it reached a step not printed yet.
+    pub fn progress_line(&self, name: &str, progress: &TransferProgress) -> Option<String> {
+        if progress.total_objects == 0 || progress.received_objects == 0 {
+            return None;
+        }
+        let step: usize = progress.received_objects * 10 / progress.total_objects;
+        let mut printed_steps = self.printed_steps.lock().unwrap();
+        if printed_steps.get(name).is_some_and(|&printed| printed >= step) {
+            return None;
+        }
+        printed_steps.insert(name.to_string(),step);
+        Some(format!("{}: received {}/{} objects ({} KiB)",name,progress.received_objects,progress.total_objects,progress.received_bytes / 1024))
+    }
+}
+
+impl ProgressReporter for ConsoleProgress {
+    fn started(&self, name: &str) {
+        self.printed_steps.lock().unwrap().remove(name);
+        println!("Syncing {}...",name);
+    }
+
+    fn transfer(&self, name: &str, progress: &TransferProgress) {
+        if let Some(line) = self.progress_line(name,progress) {
+            println!("{}",line);
+        }
+    }
+
+    fn finished(&self, report: &RepositorySyncReport) {
+        println!("{}",report);
+    }
+}
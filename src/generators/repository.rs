@@ -1,10 +1,12 @@
 use crate::generators::prelude::*;
-use git2::{ErrorCode, build::RepoBuilder};
+use git2::{BranchType, ErrorCode, build::{RepoBuilder, CheckoutBuilder}, FetchOptions, Oid, RemoteCallbacks, Repository as GitRepository, ResetType, StatusOptions, SubmoduleUpdateOptions, WorktreeAddOptions};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 ///The struct for the repository.
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Debug, Default, Clone)]
 pub struct Repository {
-    ///The target service it will be mounted.
+    ///The name of the target service it will be mounted.
     #[serde(default)]
     pub service: String,
     ///The mount path the source code will be placed.
@@ -13,11 +15,192 @@ pub struct Repository {
     ///The name of the directory
     pub name: String,
     ///The repository url.
+    #[serde(default)]
     pub url: String,
     ///The branch to clone.
+    #[serde(default)]
     pub branch: String,
+    ///The tag to checkout instead of the branch.
+    #[serde(default)]
+    pub tag: String,
+    ///The commit to checkout instead of the branch.
+    #[serde(default)]
+    pub rev: String,
     ///Tells the launcher where to clone the repository or not.
-    pub clone: bool
+    #[serde(default)]
+    pub clone: bool,
+    ///An existing local directory used instead of cloning, absolute or relative to the base_dir.
+    #[serde(default)]
+    pub path: String,
+    ///Creates a worktree of the local clone at path for the branch under the services directory.
+    #[serde(default)]
+    pub worktree: bool,
+    ///How an existing clone is updated from the remote.
+    #[serde(default)]
+    pub sync: SyncMode,
+    ///The git authentication, overrides the git_auth of the settings.
+    #[serde(default)]
+    pub auth: Option<GitAuth>,
+    ///The number of commits to fetch. Zero fetches the full history.
+    #[serde(default)]
+    pub depth: i32,
+    ///Only fetch the configured branch.
+    #[serde(default)]
+    pub single_branch: bool,
+    ///Only checkout these paths of the repository.
+    #[serde(default)]
+    pub sparse_paths: Vec<String>,
+    ///Clone and update the submodules of the repository.
+    #[serde(default)]
+    pub recurse_submodules: bool,
+    ///The subdirectory of the repository that is mounted instead of the root.
+    #[serde(default)]
+    pub mount_subpath: String,
+    ///The mounts of the repository into the services, in addition to the service and mount_target.
+    #[serde(default)]
+    pub mounts: Vec<RepositoryMount>,
+}
+
+///The struct for a mount of a repository into a service.
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct RepositoryMount {
+    ///The name of the service the repository is mounted into.
+    pub service: String,
+    ///The mount path inside the service.
+    pub target: String,
+    ///Mounts the repository as read-only.
+    #[serde(default)]
+    pub read_only: bool,
+    ///The subdirectory of the repository that is mounted instead of the root.
+    #[serde(default)]
+    pub subpath: String,
+    ///The SELinux relabeling of the mount for Podman: "z" shares it between containers, "Z" makes it private.
+    #[serde(default)]
+    pub relabel: String,
+}
+
+impl RepositoryMount {
+    ///Returns the path that is mounted into the service, the checkout path or its subpath.
+    pub fn source(&self, path: &String) -> String {
+        match self.subpath.trim_matches('/') {
+            "" => path.clone(),
+            subpath => format!("{}/{}",path,subpath)
+        }
+    }
+}
+
+///How an existing clone is updated from the remote.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncMode {
+    ///Leave the existing clone as it is.
+    #[default]
+    None,
+    ///Fetch and fast-forward the branch, skipping it if the histories diverged.
+    FastForward,
+    ///Fetch and hard reset the branch to the remote branch.
+    Reset,
+}
+
+///The outcome of syncing a repository.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyncStatus {
+    Cloned,
+    Skipped,
+    UpToDate,
+    Updated,
+    ///The working tree has uncommitted changes so it was not touched.
+    Dirty,
+    ///The local branch cannot be fast-forwarded to the remote branch.
+    Diverged,
+    ///The local path is used as it is.
+    Local,
+}
+
+///The report of syncing a repository.
+#[derive(Debug, Clone)]
+pub struct RepositorySyncReport {
+    pub name: String,
+    pub path: String,
+    pub status: SyncStatus,
+    ///The commit before syncing, empty if the repository was cloned.
+    pub old_commit: String,
+    ///The commit after syncing.
+    pub new_commit: String,
+}
+
+///The status of the checkout of a repository.
+#[derive(Debug, Clone, Default)]
+pub struct RepositoryStatus {
+    pub name: String,
+    pub path: String,
+    ///The configured reference example: branch:main, tag:v1.0.0 or rev:1a2b3c4.
+    pub reference: String,
+    ///The checkout exists.
+    pub present: bool,
+    ///The current branch, empty if HEAD is detached.
+    pub branch: String,
+    ///The commit of HEAD.
+    pub commit: String,
+    ///The number of commits the checkout is ahead of the remote branch.
+    pub ahead: usize,
+    ///The number of commits the checkout is behind the remote branch.
+    pub behind: usize,
+    ///The number of changed and untracked files.
+    pub dirty_files: usize,
+    ///The checkout is on the configured branch, tag or rev.
+    pub matches: bool,
+}
+
+impl std::fmt::Display for RepositoryStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.present {
+            return write!(f,"{}: not present at {}",self.name,self.path);
+        }
+        if self.commit.is_empty() {
+            return write!(f,"{}: not a git repository at {}",self.name,self.path);
+        }
+        let branch: &str = if self.branch.is_empty() { "detached" } else { &self.branch };
+        write!(f,"{}: {} at {}, {} ahead, {} behind, {} dirty files",self.name,branch,self.commit.chars().take(7).collect::<String>(),self.ahead,self.behind,self.dirty_files)?;
+        if !self.matches {
+            write!(f,", expected {}",self.reference)?;
+        }
+        Ok(())
+    }
+}
+
+///The authentication and progress reporter used while syncing a repository.
+struct SyncContext<'a> {
+    auth: &'a GitAuth,
+    progress: &'a dyn ProgressReporter,
+}
+
+impl RepositorySyncReport {
+    ///Create a report with the skipped status.
+    pub fn new(name: &str, path: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.to_string(),
+            status: SyncStatus::Skipped,
+            old_commit: String::new(),
+            new_commit: String::new(),
+        }
+    }
+}
+
+impl std::fmt::Display for RepositorySyncReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let short = |commit: &String| commit.chars().take(7).collect::<String>();
+        match self.status {
+            SyncStatus::Cloned => write!(f,"Cloned {} at {} to {}",self.name,short(&self.new_commit),self.path),
+            SyncStatus::Skipped => write!(f,"Skipped syncing {}({}) because it already exists.",self.name,self.path),
+            SyncStatus::UpToDate => write!(f,"{} is up to date at {}",self.name,short(&self.new_commit)),
+            SyncStatus::Updated => write!(f,"Updated {}: {} -> {}",self.name,short(&self.old_commit),short(&self.new_commit)),
+            SyncStatus::Dirty => write!(f,"Skipped syncing {} because its working tree has uncommitted changes.",self.name),
+            SyncStatus::Diverged => write!(f,"Skipped syncing {} because it cannot be fast-forwarded from {} to {}",self.name,short(&self.old_commit),short(&self.new_commit)),
+            SyncStatus::Local => write!(f,"Using {} from the local path {}",self.name,self.path),
+        }
+    }
 }
 
 
@@ -35,17 +218,22 @@ impl Repository {
     ///         name: String::from("test"),
     ///         url: "https://github.com/j-p-d-e-v/embedded-rust-led-roulette".to_string(),
     ///         branch: "dev".to_string(),
-    ///         clone: true
+    ///         clone: true,
+    ///         ..Repository::default()
     ///     }
     /// ]);
     /// for item in &data {
-    ///     assert_eq!(!Repository::git_clone(&item.name,&item.url,&item.branch,&String::from("services")).len() > 0,true);
+    ///     assert_eq!(!Repository::git_clone(&item.name,&item.url,&item.branch,&String::from("services"),&GitAuth::default()).len() > 0,true);
     /// }
     /// ```
-    pub fn git_clone(name: &String, url: &String, branch: &String, services_dir: &String) -> String {
+    pub fn git_clone(name: &String, url: &String, branch: &String, services_dir: &String, auth: &GitAuth) -> String {
         let service_path: String = format!("{}/{}",services_dir,name);
         let mut repo_builder: RepoBuilder = RepoBuilder::new();
-        match repo_builder.branch(branch).clone(url,Path::new(&service_path)) {
+        repo_builder.fetch_options(auth.fetch_options());
+        if !branch.is_empty() {
+            repo_builder.branch(branch);
+        }
+        match repo_builder.clone(url,Path::new(&service_path)) {
             Ok(_) => {
                 println!("Successfully cloned {}({}) to {}",name,branch,&services_dir);
             }
@@ -60,4 +248,558 @@ impl Repository {
         }
         service_path.clone()
     }
-}
\ No newline at end of file
+
+    ///Returns the configured reference example: branch:main, tag:v1.0.0 or rev:1a2b3c4.
+    pub fn reference(&self) -> String {
+        if !self.rev.is_empty() {
+            format!("rev:{}",self.rev)
+        }
+        else if !self.tag.is_empty() {
+            format!("tag:{}",self.tag)
+        }
+        else {
+            format!("branch:{}",self.branch)
+        }
+    }
+
+    /// Clone the repository if it does not exist yet, otherwise update it according to the sync mode.
+    /// The auth is used unless the repository declares its own.
+    pub fn sync(&self, services_dir: &String, auth: &GitAuth) -> RepositorySyncReport {
+        self.sync_with_progress(services_dir,auth,&NoProgress)
+    }
+
+    /// Same as sync but reports the transfer progress to the progress reporter.
+    pub fn sync_with_progress(&self, services_dir: &String, auth: &GitAuth, progress: &dyn ProgressReporter) -> RepositorySyncReport {
+        if self.is_local() {
+            return self.use_local();
+        }
+        let context: SyncContext = SyncContext { auth: self.auth.as_ref().unwrap_or(auth), progress };
+        let service_path: String = format!("{}/{}",services_dir,self.name);
+        let mut report: RepositorySyncReport = RepositorySyncReport::new(&self.name,&service_path);
+        if !Path::new(&service_path).exists() {
+            self.create(&service_path,&context);
+            report.status = SyncStatus::Cloned;
+            if !self.tag.is_empty() || !self.rev.is_empty() {
+                if let Err(error) = self.checkout_reference(&service_path,&context) {
+                    panic!("Unable to checkout {} of {}: {:?}",self.reference(),self.name,error);
+                }
+            }
+            report.new_commit = Repository::head_commit(&service_path).unwrap_or_default();
+            return report;
+        }
+        if self.sync == SyncMode::None {
+            report.new_commit = Repository::head_commit(&service_path).unwrap_or_default();
+            return report;
+        }
+        match self.fetch_and_update(&service_path,&mut report,&context) {
+            Ok(_) => report,
+            Err(error) => panic!("Unable to sync {}: {:?}",self.name,error)
+        }
+    }
+
+    /// Clone or update the repository to the locked commit, or sync it if there is no locked commit.
+    pub fn sync_locked(&self, services_dir: &String, locked_commit: Option<&String>, auth: &GitAuth, progress: &dyn ProgressReporter) -> RepositorySyncReport {
+        let commit: &String = match locked_commit {
+            Some(commit) => commit,
+            None => return self.sync_with_progress(services_dir,auth,progress)
+        };
+        if self.is_local() {
+            return self.use_local();
+        }
+        let context: SyncContext = SyncContext { auth: self.auth.as_ref().unwrap_or(auth), progress };
+        let service_path: String = format!("{}/{}",services_dir,self.name);
+        let mut report: RepositorySyncReport = RepositorySyncReport::new(&self.name,&service_path);
+        if !Path::new(&service_path).exists() {
+            self.create(&service_path,&context);
+            report.status = SyncStatus::Cloned;
+        }
+        match self.checkout_commit(&service_path,commit,&mut report,&context) {
+            Ok(_) => report,
+            Err(error) => panic!("Unable to checkout locked commit {} of {}: {:?}",commit,self.name,error)
+        }
+    }
+
+    /// Sync all the repositories that are cloned or created as worktrees using the lockfile, then write the lockfile.
+    /// Local paths are used as they are and are not locked.
+    /// Up to concurrency repositories are synced at the same time.
+    /// Set update to move the repositories to the latest commits of their branch, tag or rev and refresh the lockfile,
+    /// a repository with the sync mode none is fast-forwarded.
+    pub fn sync_all(repositories: &[Repository], services_dir: &String, lock_file: &String, update: bool, auth: &GitAuth, concurrency: usize, progress: &dyn ProgressReporter) -> Vec<RepositorySyncReport> {
+        let mut lockfile: Lockfile = Lockfile::load(lock_file);
+        let jobs: Vec<(Repository, Option<String>)> = repositories.iter()
+            .filter(|r| r.is_fetched())
+            .map(|r| {
+                if update && !r.is_local() {
+                    return (r.for_update(), None);
+                }
+                let locked_commit: Option<String> = match r.is_local() {
+                    true => None,
+                    false => lockfile.find(&r.name,&r.url,&r.reference()).map(|locked| locked.commit.clone())
+                };
+                (r.clone(), locked_commit)
+            })
+            .collect();
+        let next_job: AtomicUsize = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<RepositorySyncReport>>> = Mutex::new(vec![None; jobs.len()]);
+        std::thread::scope(|scope| {
+            for _ in 0..concurrency.clamp(1,jobs.len().max(1)) {
+                scope.spawn(|| {
+                    loop {
+                        let index: usize = next_job.fetch_add(1,Ordering::SeqCst);
+                        let Some((repo, locked_commit)) = jobs.get(index) else { break };
+                        progress.started(&repo.name);
+                        let report: RepositorySyncReport = repo.sync_locked(services_dir,locked_commit.as_ref(),auth,progress);
+                        progress.finished(&report);
+                        results.lock().unwrap()[index] = Some(report);
+                    }
+                });
+            }
+        });
+        let reports: Vec<RepositorySyncReport> = results.into_inner().unwrap().into_iter().flatten().collect();
+        for ((repo, locked_commit), report) in jobs.iter().zip(&reports) {
+            if locked_commit.is_none() && ![SyncStatus::Dirty, SyncStatus::Diverged, SyncStatus::Local].contains(&report.status) {
+                lockfile.lock(LockedRepository {
+                    name: repo.name.clone(),
+                    url: repo.url.clone(),
+                    reference: repo.reference(),
+                    commit: report.new_commit.clone()
+                });
+            }
+        }
+        lockfile.repositories.retain(|locked| repositories.iter().any(|r| r.is_fetched() && !r.is_local() && r.name == locked.name));
+        if let Err(error) = lockfile.write(lock_file) {
+            panic!("Unable to write lockfile {}: {:?}",lock_file,error);
+        }
+        reports
+    }
+
+    ///Returns the repository to sync when updating, an existing clone with the sync mode none is fast-forwarded.
+    ///Panics if the repository has no branch, tag or rev to update to.
+    fn for_update(&self) -> Repository {
+        if self.branch.is_empty() && self.tag.is_empty() && self.rev.is_empty() {
+            panic!("Unable to update repository named {} without a branch, tag or rev.",self.name);
+        }
+        match self.sync {
+            SyncMode::None => Repository { sync: SyncMode::FastForward, ..self.clone() },
+            _ => self.clone()
+        }
+    }
+
+    /// Returns the status of the checkout of the repository without touching the network.
+    /// Set fetch to fetch origin first so ahead and behind are compared with the latest remote branch.
+    pub fn status(&self, services_dir: &String, fetch: bool, auth: &GitAuth) -> RepositoryStatus {
+        let mut status: RepositoryStatus = RepositoryStatus {
+            name: self.name.clone(),
+            path: self.checkout_path(services_dir),
+            reference: self.reference(),
+            ..RepositoryStatus::default()
+        };
+        if !Path::new(&status.path).exists() {
+            return status;
+        }
+        status.present = true;
+        if let Err(error) = self.read_status(&mut status,fetch,auth) {
+            panic!("Unable to read the status of {}: {:?}",self.name,error);
+        }
+        status
+    }
+
+    /// Returns the status of every repository that is cloned, a local path or a worktree.
+    pub fn status_all(repositories: &[Repository], services_dir: &String, fetch: bool, auth: &GitAuth) -> Vec<RepositoryStatus> {
+        repositories.iter()
+            .filter(|r| r.is_fetched())
+            .map(|r| r.status(services_dir,fetch,auth))
+            .collect()
+    }
+
+    ///Returns the commit id of HEAD of a local repository.
+    pub fn head_commit(path: &String) -> Result<String, git2::Error> {
+        let repo: GitRepository = GitRepository::open(path)?;
+        let commit = repo.head()?.peel_to_commit()?;
+        Ok(commit.id().to_string())
+    }
+
+    ///Returns the path that is mounted into the service, the checkout path or its mount_subpath.
+    pub fn mount_source(&self, path: &String) -> String {
+        RepositoryMount { subpath: self.mount_subpath.clone(), ..RepositoryMount::default() }.source(path)
+    }
+
+    ///Returns the mounts of the repository, the service and mount_target first if the service is set, then the mounts.
+    pub fn mounts(&self) -> Vec<RepositoryMount> {
+        let mut mounts: Vec<RepositoryMount> = Vec::new();
+        if !self.service.is_empty() {
+            mounts.push(RepositoryMount {
+                service: self.service.clone(),
+                target: self.mount_target.clone(),
+                subpath: self.mount_subpath.clone(),
+                ..RepositoryMount::default()
+            });
+        }
+        mounts.extend(self.mounts.iter().cloned());
+        mounts
+    }
+
+    ///Checks if the path stays inside the repository, a relative path without '..'.
+    pub fn is_inner_path(path: &str) -> bool {
+        !Path::new(path).has_root() && !Path::new(path).components().any(|c| c == std::path::Component::ParentDir)
+    }
+
+    ///Returns the path of each repository that is present by name, the local path or the directory under the services directory.
+    pub fn local_paths(repositories: &[Repository], services_dir: &String) -> HashMap<String,String> {
+        repositories.iter()
+            .map(|repo| (repo.name.clone(),repo.checkout_path(services_dir)))
+            .filter(|(_, path)| Path::new(path).exists())
+            .collect()
+    }
+
+    ///Returns the directory of the repository, the local path or the directory under the services directory.
+    pub fn checkout_path(&self, services_dir: &String) -> String {
+        match self.is_local() {
+            true => self.path.clone(),
+            false => format!("{}/{}",services_dir,self.name)
+        }
+    }
+
+    ///Checks if the repository uses the local path as it is.
+    pub fn is_local(&self) -> bool {
+        !self.path.is_empty() && !self.worktree
+    }
+
+    ///Checks if the launcher provides the repository, either cloned, a local path or a worktree.
+    pub fn is_fetched(&self) -> bool {
+        self.clone || !self.path.is_empty()
+    }
+
+    ///Validates the source and the mounts of the repository:
+    /// - the name is used as a single directory so it cannot be empty, `.`, `..`, absolute or contain a `/`.
+    /// - a worktree requires the path of the local clone and a branch.
+    /// - a repository cannot be cloned and use a local path at the same time.
+    /// - a cloned repository requires a url, and a branch, tag or rev when it is synced.
+    /// - each mount requires a service and an absolute target, a relative subpath without '..', and the relabel must be "z" or "Z".
+    pub fn validate(&self) {
+        let mut components = Path::new(&self.name).components();
+        if !matches!((components.next(), components.next()), (Some(std::path::Component::Normal(_)), None)) {
+            panic!("Repository name {} must be a single directory name, not '.', '..' or a path.",self.name);
+        }
+        if self.worktree && self.path.is_empty() {
+            panic!("Unable to create a worktree of repository named {} without a path.",self.name);
+        }
+        if self.worktree && self.branch.is_empty() {
+            panic!("Unable to create a worktree of repository named {} without a branch.",self.name);
+        }
+        if self.clone && !self.path.is_empty() {
+            panic!("Repository named {} cannot be cloned and use the local path {} at the same time.",self.name,self.path);
+        }
+        if self.clone && self.url.is_empty() {
+            panic!("Unable to clone repository named {} without a url.",self.name);
+        }
+        if self.clone && self.sync != SyncMode::None && self.branch.is_empty() && self.tag.is_empty() && self.rev.is_empty() {
+            panic!("Unable to sync repository named {} without a branch, tag or rev.",self.name);
+        }
+        for mount in &self.mounts() {
+            if mount.service.is_empty() {
+                panic!("Mount of repository named {} requires a service.",self.name);
+            }
+            if !mount.target.starts_with('/') {
+                panic!("Mount target {} of repository named {} into service named {} must be an absolute container path.",mount.target,self.name,mount.service);
+            }
+            if !Repository::is_inner_path(&mount.subpath) {
+                panic!("Mount subpath {} of repository named {} into service named {} must be a relative path without '..'.",mount.subpath,self.name,mount.service);
+            }
+            if !["", "z", "Z"].contains(&mount.relabel.as_str()) {
+                panic!("Mount of repository named {} into service named {} has an invalid relabel {}. Expected z or Z.",self.name,mount.service,mount.relabel);
+            }
+        }
+    }
+
+    ///Reads the branch, commit, ahead and behind counts, dirty files inside the sparse paths and whether the checkout matches the reference.
+    fn read_status(&self, status: &mut RepositoryStatus, fetch: bool, auth: &GitAuth) -> Result<(), git2::Error> {
+        let Ok(repo) = GitRepository::open(&status.path) else {
+            return Ok(());
+        };
+        if fetch {
+            let context: SyncContext = SyncContext { auth: self.auth.as_ref().unwrap_or(auth), progress: &NoProgress };
+            repo.find_remote("origin")?.fetch::<&str>(&[],Some(&mut self.fetch_options(&context)),None)?;
+        }
+        let head = repo.head()?;
+        let commit: Oid = head.peel_to_commit()?.id();
+        status.commit = commit.to_string();
+        if head.is_branch() {
+            status.branch = head.shorthand().unwrap_or_default().to_string();
+        }
+
+        let mut status_options: StatusOptions = StatusOptions::new();
+        status_options.include_untracked(true).include_ignored(false).exclude_submodules(true);
+        //The files outside the sparse paths are missing from the working tree on purpose.
+        for sparse_path in &self.sparse_paths {
+            status_options.pathspec(sparse_path);
+        }
+        status.dirty_files = repo.statuses(Some(&mut status_options))?.len();
+
+        let remote_branch: &String = if status.branch.is_empty() { &self.branch } else { &status.branch };
+        if let Ok(remote) = repo.find_reference(&format!("refs/remotes/origin/{}",remote_branch)) {
+            (status.ahead, status.behind) = repo.graph_ahead_behind(commit,remote.peel_to_commit()?.id())?;
+        }
+
+        status.matches = match self.tag.is_empty() && self.rev.is_empty() {
+            true => self.branch.is_empty() || status.branch == self.branch,
+            false => self.resolve_reference(&repo).map(|id| id == commit).unwrap_or(false)
+        };
+        Ok(())
+    }
+
+    ///Creates the report of a repository that uses the local path as it is.
+    fn use_local(&self) -> RepositorySyncReport {
+        if !Path::new(&self.path).exists() {
+            panic!("Unable to find the local path {} of repository named {}.",self.path,self.name);
+        }
+        let mut report: RepositorySyncReport = RepositorySyncReport::new(&self.name,&self.path);
+        report.status = SyncStatus::Local;
+        report.new_commit = Repository::head_commit(&self.path).unwrap_or_default();
+        report
+    }
+
+    ///Creates the checkout, a worktree of the local clone or a clone of the url.
+    fn create(&self, path: &String, context: &SyncContext<'_>) {
+        match self.worktree {
+            true => self.add_worktree(path),
+            false => self.clone_into(path,context)
+        }
+    }
+
+    ///Adds a worktree of the local clone for the branch, creating the local branch from origin if it does not exist.
+    fn add_worktree(&self, path: &String) {
+        let result = GitRepository::open(&self.path).and_then(|repo| {
+            let branch = match repo.find_branch(&self.branch,BranchType::Local) {
+                Ok(branch) => branch,
+                Err(_) => {
+                    let commit = repo.find_reference(&format!("refs/remotes/origin/{}",self.branch))?.peel_to_commit()?;
+                    repo.branch(&self.branch,&commit,false)?
+                }
+            };
+            let mut options: WorktreeAddOptions = WorktreeAddOptions::new();
+            options.reference(Some(branch.get()));
+            repo.worktree(&self.name,Path::new(path),Some(&options))?;
+            Ok(())
+        });
+        match result {
+            Ok(_) => println!("Successfully created worktree {}({}) of {} at {}",self.name,self.branch,self.path,path),
+            Err(error) => panic!("Unable to create worktree {} of {}: {:?}",self.name,self.path,error)
+        }
+    }
+
+    ///Clones the repository with the depth, single branch, sparse paths and submodules options.
+    fn clone_into(&self, path: &String, context: &SyncContext<'_>) {
+        let mut repo_builder: RepoBuilder = RepoBuilder::new();
+        repo_builder.fetch_options(self.fetch_options(context));
+        repo_builder.with_checkout(self.checkout_builder());
+        if !self.branch.is_empty() {
+            repo_builder.branch(&self.branch);
+            if self.single_branch {
+                let branch: String = self.branch.clone();
+                repo_builder.remote_create(move |repo, name, url| {
+                    repo.remote_with_fetch(name,url,&format!("+refs/heads/{0}:refs/remotes/{1}/{0}",branch,name))
+                });
+            }
+        }
+        let result = repo_builder.clone(&self.url,Path::new(path)).and_then(|repo| {
+            if !self.sparse_paths.is_empty() {
+                self.write_sparse_checkout(&repo)?;
+            }
+            if self.recurse_submodules {
+                self.update_submodules(&repo,context)?;
+            }
+            Ok(())
+        });
+        match result {
+            Ok(_) => println!("Successfully cloned {}({}) to {}",self.name,self.reference(),path),
+            Err(error) => panic!("Unable to clone {}: {:?}",self.name,error)
+        }
+    }
+
+    ///Creates the fetch options with the authentication, the progress and the depth.
+    fn fetch_options<'a>(&'a self, context: &'a SyncContext<'_>) -> FetchOptions<'a> {
+        let mut callbacks: RemoteCallbacks = context.auth.remote_callbacks();
+        callbacks.transfer_progress(move |stats| {
+            context.progress.transfer(&self.name,&TransferProgress {
+                received_objects: stats.received_objects(),
+                indexed_objects: stats.indexed_objects(),
+                total_objects: stats.total_objects(),
+                received_bytes: stats.received_bytes(),
+            });
+            true
+        });
+        let mut fetch_options: FetchOptions = FetchOptions::new();
+        fetch_options.remote_callbacks(callbacks);
+        if self.depth > 0 {
+            fetch_options.depth(self.depth);
+        }
+        fetch_options
+    }
+
+    ///Creates a forced checkout limited to the sparse paths.
+    fn checkout_builder(&self) -> CheckoutBuilder<'_> {
+        let mut checkout: CheckoutBuilder = CheckoutBuilder::new();
+        checkout.force();
+        for sparse_path in &self.sparse_paths {
+            checkout.path(sparse_path);
+        }
+        checkout
+    }
+
+    ///Writes the sparse checkout patterns so the git command line keeps the same paths checked out.
+    fn write_sparse_checkout(&self, repo: &GitRepository) -> Result<(), git2::Error> {
+        let info_dir: PathBuf = repo.path().join("info");
+        let patterns: String = self.sparse_paths.iter().map(|p| format!("/{}\n",p.trim_start_matches('/'))).collect();
+        std::fs::create_dir_all(&info_dir)
+            .and_then(|_| std::fs::write(info_dir.join("sparse-checkout"),patterns))
+            .map_err(|error| git2::Error::from_str(&error.to_string()))?;
+        repo.config()?.set_bool("core.sparseCheckout",true)
+    }
+
+    ///Initializes and updates the submodules recursively.
+    fn update_submodules(&self, repo: &GitRepository, context: &SyncContext<'_>) -> Result<(), git2::Error> {
+        for mut submodule in repo.submodules()? {
+            let mut update_options: SubmoduleUpdateOptions = SubmoduleUpdateOptions::new();
+            update_options.fetch(self.fetch_options(context));
+            submodule.update(true,Some(&mut update_options))?;
+            self.update_submodules(&submodule.open()?,context)?;
+        }
+        Ok(())
+    }
+
+    ///Checks if the working tree has uncommitted changes to tracked files inside the sparse paths.
+    fn is_dirty(&self, repo: &GitRepository) -> Result<bool, git2::Error> {
+        let mut status_options: StatusOptions = StatusOptions::new();
+        status_options.include_untracked(false).include_ignored(false).exclude_submodules(true);
+        for sparse_path in &self.sparse_paths {
+            status_options.pathspec(sparse_path);
+        }
+        Ok(!repo.statuses(Some(&mut status_options))?.is_empty())
+    }
+
+    ///Updates the submodules after the checkout moved, if enabled.
+    fn after_checkout(&self, repo: &GitRepository, context: &SyncContext<'_>) -> Result<(), git2::Error> {
+        if self.recurse_submodules {
+            self.update_submodules(repo,context)?;
+        }
+        Ok(())
+    }
+
+    ///Fetches the commit by its id from origin if it is not available locally.
+    ///Fetching the branches is not enough for a shallow checkout, they only bring the last depth commits.
+    fn fetch_commit(&self, repo: &GitRepository, commit: Oid, context: &SyncContext<'_>) -> Result<(), git2::Error> {
+        if repo.find_commit(commit).is_err() {
+            repo.find_remote("origin")?.fetch(&[format!("+{}:refs/rustack/locked",commit)],Some(&mut self.fetch_options(context)),None)?;
+        }
+        Ok(())
+    }
+
+    ///Fetches the configured rev from origin if it is a full commit id that is not available locally.
+    fn fetch_rev(&self, repo: &GitRepository, context: &SyncContext<'_>) -> Result<(), git2::Error> {
+        match Oid::from_str(&self.rev) {
+            Ok(commit) if self.rev.len() == 40 => self.fetch_commit(repo,commit,context),
+            _ => Ok(())
+        }
+    }
+
+    ///Resolves the configured tag or rev to a commit.
+    fn resolve_reference(&self, repo: &GitRepository) -> Result<Oid, git2::Error> {
+        let spec: String = if self.rev.is_empty() { format!("refs/tags/{}",self.tag) } else { self.rev.clone() };
+        Ok(repo.revparse_single(&spec)?.peel_to_commit()?.id())
+    }
+
+    ///Checks out the configured tag or rev as a detached HEAD.
+    fn checkout_reference(&self, path: &String, context: &SyncContext<'_>) -> Result<(), git2::Error> {
+        let repo: GitRepository = GitRepository::open(path)?;
+        self.fetch_rev(&repo,context)?;
+        let commit: Oid = self.resolve_reference(&repo)?;
+        repo.set_head_detached(commit)?;
+        repo.checkout_head(Some(&mut self.checkout_builder()))?;
+        self.after_checkout(&repo,context)
+    }
+
+    ///Checks out a commit as a detached HEAD, fetching from origin if the commit is not available locally.
+    fn checkout_commit(&self, path: &String, commit: &str, report: &mut RepositorySyncReport, context: &SyncContext<'_>) -> Result<(), git2::Error> {
+        let repo: GitRepository = GitRepository::open(path)?;
+        let old_commit: Oid = repo.head()?.peel_to_commit()?.id();
+        let new_commit: Oid = Oid::from_str(commit)?;
+        report.old_commit = old_commit.to_string();
+        report.new_commit = old_commit.to_string();
+        if old_commit == new_commit {
+            if report.status != SyncStatus::Cloned {
+                report.status = SyncStatus::UpToDate;
+            }
+            return Ok(());
+        }
+        if self.is_dirty(&repo)? {
+            report.status = SyncStatus::Dirty;
+            return Ok(());
+        }
+        self.fetch_commit(&repo,new_commit,context)?;
+        repo.set_head_detached(new_commit)?;
+        repo.checkout_head(Some(&mut self.checkout_builder()))?;
+        self.after_checkout(&repo,context)?;
+        report.new_commit = new_commit.to_string();
+        if report.status != SyncStatus::Cloned {
+            report.status = SyncStatus::Updated;
+        }
+        Ok(())
+    }
+
+    ///Fetches the branch, tag or rev from origin then fast-forwards or resets the local checkout to it.
+    fn fetch_and_update(&self, path: &String, report: &mut RepositorySyncReport, context: &SyncContext<'_>) -> Result<(), git2::Error> {
+        let repo: GitRepository = GitRepository::open(path)?;
+        let old_commit = repo.head()?.peel_to_commit()?.id();
+        report.old_commit = old_commit.to_string();
+        report.new_commit = old_commit.to_string();
+
+        if self.is_dirty(&repo)? {
+            report.status = SyncStatus::Dirty;
+            return Ok(());
+        }
+
+        if !self.tag.is_empty() || !self.rev.is_empty() {
+            match self.tag.is_empty() {
+                true => repo.find_remote("origin")?.fetch::<&str>(&[],Some(&mut self.fetch_options(context)),None)?,
+                false => repo.find_remote("origin")?.fetch(&[format!("+refs/tags/{0}:refs/tags/{0}",self.tag)],Some(&mut self.fetch_options(context)),None)?
+            }
+            self.fetch_rev(&repo,context)?;
+            let new_commit: Oid = self.resolve_reference(&repo)?;
+            report.new_commit = new_commit.to_string();
+            if new_commit == old_commit {
+                report.status = SyncStatus::UpToDate;
+                return Ok(());
+            }
+            repo.set_head_detached(new_commit)?;
+            repo.checkout_head(Some(&mut self.checkout_builder()))?;
+            self.after_checkout(&repo,context)?;
+            report.status = SyncStatus::Updated;
+            return Ok(());
+        }
+
+        repo.find_remote("origin")?.fetch(&[&self.branch],Some(&mut self.fetch_options(context)),None)?;
+        let new_commit = repo.find_reference(&format!("refs/remotes/origin/{}",self.branch))?.peel_to_commit()?;
+        report.new_commit = new_commit.id().to_string();
+
+        let branch_ref: String = format!("refs/heads/{}",self.branch);
+        let on_branch: bool = repo.head()?.name() == Some(branch_ref.as_str());
+        if new_commit.id() == old_commit && on_branch {
+            report.status = SyncStatus::UpToDate;
+            return Ok(());
+        }
+        if self.sync == SyncMode::FastForward && !repo.graph_descendant_of(new_commit.id(),old_commit)? && new_commit.id() != old_commit {
+            report.status = SyncStatus::Diverged;
+            return Ok(());
+        }
+        repo.reference(&branch_ref,new_commit.id(),true,"rustack-launcher: sync")?;
+        repo.set_head(&branch_ref)?;
+        match self.sync {
+            SyncMode::Reset => repo.reset(new_commit.as_object(),ResetType::Hard,Some(&mut self.checkout_builder()))?,
+            _ => repo.checkout_head(Some(&mut self.checkout_builder()))?
+        }
+        self.after_checkout(&repo,context)?;
+        report.status = SyncStatus::Updated;
+        Ok(())
+    }
+}
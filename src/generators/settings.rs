@@ -20,5 +20,64 @@ pub struct Settings {
     ///The target compose yaml file
     pub compose_file: String,
     ///Execute the compose file in detached mode.
-    pub compose_detached: bool
+    pub compose_detached: bool,
+    ///Wait for the services with healthchecks to become healthy after a detached up.
+    #[serde(default)]
+    pub compose_wait: bool,
+    ///The number of seconds to wait for the services to become healthy.
+    #[serde(default = "default_compose_wait_timeout")]
+    pub compose_wait_timeout: u64,
+    ///The lockfile that records the resolved commit of each repository, relative to the base directory.
+    #[serde(default = "default_lock_file")]
+    pub lock_file: String,
+    ///The git authentication used by repositories that do not declare their own.
+    #[serde(default)]
+    pub git_auth: GitAuth,
+    ///The number of repositories cloned or fetched at the same time.
+    #[serde(default = "default_clone_concurrency")]
+    pub clone_concurrency: usize,
+    ///The file name of the env files, {name} is replaced by the name of the env file.
+    #[serde(default = "default_env_file_pattern")]
+    pub env_file_pattern: String,
+    ///The subdirectory of the deploy directory the env files are written to.
+    #[serde(default)]
+    pub env_file_dir: String,
+    ///Refer to the env files with absolute paths in the compose file instead of paths relative to it.
+    #[serde(default)]
+    pub env_file_absolute_paths: bool,
+}
+
+impl Settings {
+    ///Returns where the env files are written and how the compose file refers to them.
+    pub fn env_file_layout(&self) -> EnvFileLayout {
+        EnvFileLayout {
+            pattern: self.env_file_pattern.clone(),
+            dir: self.env_file_dir.clone(),
+            absolute: self.env_file_absolute_paths,
+        }
+    }
+
+    ///Returns the path with a relative path resolved against the base_dir, an empty path is left empty.
+    pub fn resolve_path(&self, path: &str) -> String {
+        match path.is_empty() || Path::new(path).is_absolute() {
+            true => path.to_string(),
+            false => format!("{}/{}",self.base_dir,path.strip_prefix("./").unwrap_or(path))
+        }
+    }
+}
+
+fn default_compose_wait_timeout() -> u64 {
+    120
+}
+
+fn default_lock_file() -> String {
+    String::from("rustack.lock")
+}
+
+fn default_clone_concurrency() -> usize {
+    4
+}
+
+fn default_env_file_pattern() -> String {
+    EnvFileLayout::default().pattern
 }
@@ -0,0 +1,32 @@
+use crate::generators::prelude::*;
+use git2::CredentialType;
+
+#[test]
+fn test_token_from_environment() {
+    let auth: GitAuth = GitAuth {
+        method: GitAuthMethod::Token,
+        token_env: String::from("RUSTACK_TEST_GIT_TOKEN"),
+        ..GitAuth::default()
+    };
+    std::env::set_var("RUSTACK_TEST_GIT_TOKEN","secret");
+    let cred = auth.credentials("https://example.com/app.git",None,CredentialType::USER_PASS_PLAINTEXT).unwrap();
+    assert!(cred.has_username());
+}
+
+#[test]
+fn test_token_environment_not_set() {
+    let auth: GitAuth = GitAuth {
+        method: GitAuthMethod::Token,
+        token_env: String::from("RUSTACK_TEST_GIT_TOKEN_MISSING"),
+        ..GitAuth::default()
+    };
+    let error = auth.credentials("https://example.com/app.git",None,CredentialType::USER_PASS_PLAINTEXT).err().unwrap();
+    assert!(error.message().contains("RUSTACK_TEST_GIT_TOKEN_MISSING"));
+}
+
+#[test]
+#[should_panic(expected = "must declare the ssh_key file")]
+fn test_ssh_key_without_file() {
+    let auth: GitAuth = toml::from_str("method = \"ssh_key\"").unwrap();
+    auth.validate("settings");
+}
@@ -0,0 +1,295 @@
+use crate::generators::prelude::*;
+use crate::generators::tests::helpers::{ temp_dir, parse_config };
+use std::fs::create_dir_all;
+
+const CONFIG: &str = r#"
+[settings]
+name = "test"
+author = "test"
+description = "test"
+base_dir = "."
+deploy_dir = "deploy"
+services_dir = "services"
+compose_executable = "docker-compose"
+compose_file = "compose.yaml"
+compose_detached = true
+
+[[services]]
+hostname = "db"
+image = "postgres"
+secrets = ["db_password"]
+configs = ["pg_conf"]
+
+[[env_files]]
+name = "common"
+values = []
+
+[[secrets]]
+name = "db_password"
+file = "./db_password.txt"
+
+[[secrets]]
+name = "api_token"
+environment = "API_TOKEN"
+
+[[configs]]
+name = "pg_conf"
+file = "./postgresql.conf"
+"#;
+
+///Points the base_dir of the configuration to a new test directory and returns the deploy directory under it.
+fn deploy_to(config: &mut Config, name: &str) -> String {
+    config.settings.base_dir = temp_dir(name);
+    let deploy_dir: String = format!("{}/{}",config.settings.base_dir,config.settings.deploy_dir);
+    create_dir_all(&deploy_dir).unwrap();
+    deploy_dir
+}
+
+#[test]
+fn test_compose_secrets_and_configs() {
+    let mut config: Config = parse_config(CONFIG);
+    config.validate();
+    deploy_to(&mut config,"compose-secrets");
+    let compose: Compose = Compose::generate(&config,&HashMap::new());
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    assert_eq!(data["secrets"]["db_password"]["file"],Value::from(format!("{}/db_password.txt",config.settings.base_dir)));
+    assert_eq!(data["secrets"]["api_token"]["environment"],Value::from("API_TOKEN"));
+    assert!(data["secrets"]["api_token"].get("file").is_none());
+    assert_eq!(data["configs"]["pg_conf"]["file"],Value::from(format!("{}/postgresql.conf",config.settings.base_dir)));
+    assert_eq!(data["services"]["db"]["secrets"][0],Value::from("db_password"));
+    assert_eq!(data["services"]["db"]["configs"][0],Value::from("pg_conf"));
+}
+
+#[test]
+fn test_compose_service_options() {
+    let mut config: Config = parse_config(&CONFIG.replace(r#"configs = ["pg_conf"]"#,r#"configs = ["pg_conf"]
+command = ["postgres", "-c", "log_statement=all"]
+entrypoint = "docker-entrypoint.sh"
+container_name = "my-db"
+cap_add = ["SYS_NICE"]
+extra_hosts = ["host.docker.internal:host-gateway"]
+expose = ["5432"]
+stop_grace_period = "1m30s"
+healthcheck = { test = ["CMD", "pg_isready"], interval = "10s", retries = 5 }
+ulimits = { nproc = 65535, nofile = { soft = 20000, hard = 40000 } }
+logging = { driver = "json-file", options = { max-size = "10m" } }
+deploy = { resources = { limits = { cpus = "0.5", memory = "512M" } } }
+init = true
+shm_size = "256m""#));
+    config.validate();
+    deploy_to(&mut config,"compose-options");
+    let compose: Compose = Compose::generate(&config,&HashMap::new());
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    let service: &Value = &data["services"]["db"];
+    assert_eq!(service["command"][2],Value::from("log_statement=all"));
+    assert_eq!(service["entrypoint"],Value::from("docker-entrypoint.sh"));
+    assert_eq!(service["container_name"],Value::from("my-db"));
+    assert_eq!(service["healthcheck"]["test"][1],Value::from("pg_isready"));
+    assert_eq!(service["healthcheck"]["retries"],Value::from(5));
+    assert_eq!(service["ulimits"]["nproc"],Value::from(65535));
+    assert_eq!(service["ulimits"]["nofile"]["hard"],Value::from(40000));
+    assert_eq!(service["logging"]["options"]["max-size"],Value::from("10m"));
+    assert_eq!(service["deploy"]["resources"]["limits"]["memory"],Value::from("512M"));
+    assert_eq!(service["init"],Value::from(true));
+    assert_eq!(service["shm_size"],Value::from("256m"));
+}
+
+#[test]
+fn test_compose_build_from_repository() {
+    let mut config: Config = parse_config(&format!("{}{}",CONFIG.replace(r#"image = "postgres""#,r#"build = { repository = "app", context = "docker", dockerfile = "Dockerfile.dev", args = { VERSION = "1" }, cache_from = ["app:latest"] }"#),r#"
+[[repositories]]
+name = "app"
+url = "https://example.com/app.git"
+branch = "main"
+clone = false
+"#));
+    config.validate();
+    deploy_to(&mut config,"compose-build");
+    let compose: Compose = Compose::generate(&config,&HashMap::from([(String::from("app"),String::from("/srv/services/app"))]));
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    let build: &Value = &data["services"]["db"]["build"];
+    assert_eq!(build["context"],Value::from("/srv/services/app/docker"));
+    assert_eq!(build["dockerfile"],Value::from("Dockerfile.dev"));
+    assert_eq!(build["args"]["VERSION"],Value::from("1"));
+    assert!(build.get("repository").is_none());
+    assert!(data["services"]["db"].get("image").is_none());
+}
+
+#[test]
+fn test_compose_build_context() {
+    let mut config: Config = parse_config(&CONFIG.replace(r#"image = "postgres""#,r#"build = { context = "./docker" }"#));
+    config.validate();
+    deploy_to(&mut config,"compose-build-context");
+    let compose: Compose = Compose::generate(&config,&HashMap::new());
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    assert_eq!(data["services"]["db"]["build"]["context"],Value::from(format!("{}/docker",config.settings.base_dir)));
+
+    config.services[0].build.as_mut().unwrap().context = String::from("https://github.com/docker/rootfs.git#main");
+    let compose: Compose = Compose::generate(&config,&HashMap::new());
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    assert_eq!(data["services"]["db"]["build"]["context"],Value::from("https://github.com/docker/rootfs.git#main"));
+}
+
+#[test]
+fn test_compose_depends_on_conditions() {
+    let mut config: Config = parse_config(&format!("{}{}",CONFIG.replace(r#"image = "postgres""#,r#"image = "postgres"
+healthcheck = { test = ["CMD", "pg_isready"] }"#),r#"
+[[services]]
+hostname = "app"
+image = "app"
+depends_on = { db = { condition = "service_healthy" } }
+
+[[services]]
+hostname = "adminer"
+image = "adminer"
+depends_on = ["db"]
+"#));
+    config.validate();
+    deploy_to(&mut config,"compose-depends-on");
+    let compose: Compose = Compose::generate(&config,&HashMap::new());
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    assert_eq!(data["services"]["app"]["depends_on"]["db"]["condition"],Value::from("service_healthy"));
+    assert_eq!(data["services"]["adminer"]["depends_on"][0],Value::from("db"));
+    assert_eq!(compose.health_checked_services(),vec![String::from("db")]);
+}
+
+#[test]
+fn test_compose_service_name() {
+    let mut config: Config = parse_config(&format!("{}{}",CONFIG,r#"
+[[services]]
+name = "worker-1"
+hostname = "worker"
+image = "app"
+depends_on = ["db"]
+
+[[services]]
+name = "worker-2"
+image = "app"
+depends_on = ["worker-1"]
+"#));
+    config.validate();
+    deploy_to(&mut config,"compose-service-name");
+    let compose: Compose = Compose::generate(&config,&HashMap::new());
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    assert_eq!(data["services"]["db"]["hostname"],Value::from("db"));
+    assert_eq!(data["services"]["worker-1"]["hostname"],Value::from("worker"));
+    assert!(data["services"]["worker-2"].get("hostname").is_none());
+    assert!(data["services"]["worker-2"].get("name").is_none());
+}
+
+#[test]
+#[should_panic(expected = "is not present. Fetch the repositories first.")]
+fn test_compose_repository_not_fetched() {
+    let mut config: Config = parse_config(&format!("{}{}",CONFIG,r#"
+[[repositories]]
+service = "db"
+mount_target = "/var/app"
+name = "app"
+url = "https://example.com/app.git"
+branch = "main"
+clone = true
+"#));
+    deploy_to(&mut config,"compose-repository-not-fetched");
+    Compose::generate(&config,&HashMap::new());
+}
+
+#[test]
+fn test_compose_repository_mounts() {
+    let mut config: Config = parse_config(&format!("{}{}",CONFIG,r#"
+[[services]]
+name = "worker"
+image = "app"
+
+[[repositories]]
+service = "db"
+mount_target = "/var/app"
+name = "app"
+url = "https://example.com/app.git"
+branch = "main"
+clone = true
+mounts = [
+    { service = "worker", target = "/app", read_only = true, subpath = "worker", relabel = "z" },
+    { service = "db", target = "/docker-entrypoint-initdb.d", subpath = "sql", relabel = "Z" },
+]
+"#));
+    config.validate();
+    deploy_to(&mut config,"compose-repository-mounts");
+    let compose: Compose = Compose::generate(&config,&HashMap::from([(String::from("app"),String::from("/srv/services/app"))]));
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    let db_volumes: &Value = &data["services"]["db"]["volumes"];
+    assert_eq!(db_volumes[0]["source"],Value::from("/srv/services/app"));
+    assert_eq!(db_volumes[0]["read_only"],Value::from(false));
+    assert!(db_volumes[0].get("bind").is_none());
+    assert_eq!(db_volumes[1]["source"],Value::from("/srv/services/app/sql"));
+    assert_eq!(db_volumes[1]["bind"]["selinux"],Value::from("Z"));
+    let worker_volume: &Value = &data["services"]["worker"]["volumes"][0];
+    assert_eq!(worker_volume["type"],Value::from("bind"));
+    assert_eq!(worker_volume["source"],Value::from("/srv/services/app/worker"));
+    assert_eq!(worker_volume["target"],Value::from("/app"));
+    assert_eq!(worker_volume["read_only"],Value::from(true));
+    assert_eq!(worker_volume["bind"]["selinux"],Value::from("z"));
+}
+
+#[test]
+fn test_compose_env_file_layout() {
+    let config_data: String = CONFIG.replace("compose_detached = true","compose_detached = true\nenv_file_pattern = \"{name}.env\"\nenv_file_dir = \"env\"")
+        .replace("configs = [\"pg_conf\"]","configs = [\"pg_conf\"]\nenv_file = [\"common\"]");
+    let mut config: Config = parse_config(&config_data);
+    config.validate();
+    let deploy_dir: String = deploy_to(&mut config,"compose-env-file-layout");
+    let file_paths: Vec<String> = EnvironmentFile::generate(&config.env_files,&deploy_dir,&config.settings.env_file_layout());
+    assert_eq!(file_paths,vec![format!("{}/env/common.env",deploy_dir)]);
+    assert!(Path::new(&file_paths[0]).exists());
+    let compose: Compose = Compose::generate(&config,&HashMap::new());
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    assert_eq!(data["services"]["db"]["env_file"][0],Value::from("env/common.env"));
+
+    let base_dir: String = config.settings.base_dir.clone();
+    let mut config: Config = parse_config(&config_data.replace("env_file_dir = \"env\"","env_file_dir = \"env\"\nenv_file_absolute_paths = true"));
+    config.settings.base_dir = base_dir;
+    let compose: Compose = Compose::generate(&config,&HashMap::new());
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    assert_eq!(data["services"]["db"]["env_file"][0],Value::from(file_paths[0].clone()));
+}
+
+#[test]
+fn test_compose_service_inherit() {
+    std::env::set_var("RUSTACK_TEST_SERVICE_UID","1000");
+    let mut config: Config = parse_config(&CONFIG.replace("configs = [\"pg_conf\"]","configs = [\"pg_conf\"]\ninherit = [\"RUSTACK_TEST_SERVICE_UID\", { name = \"RUSTACK_TEST_SERVICE_PROXY\", required = false }]"));
+    config.validate();
+    deploy_to(&mut config,"compose-service-inherit");
+    let compose: Compose = Compose::generate(&config,&HashMap::new());
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    let service: &Value = &data["services"]["db"];
+    assert_eq!(service["environment"]["RUSTACK_TEST_SERVICE_UID"],Value::from("1000"));
+    assert!(service["environment"].get("RUSTACK_TEST_SERVICE_PROXY").is_none());
+    assert!(service.get("inherit").is_none());
+}
+
+#[test]
+fn test_compose_project_env() {
+    let base_dir: String = temp_dir("compose-project-env");
+    std::fs::create_dir_all(format!("{}/deploy",base_dir)).unwrap();
+    let config: Config = parse_config(&format!("{}\n[project_env]\nvalues = {{ POSTGRES_TAG = \"16\" }}\n",CONFIG.replace("base_dir = \".\"",&format!("base_dir = \"{}\"",base_dir))));
+    let compose: Compose = Compose::new(config);
+    assert_eq!(compose.env_file,format!("{}/deploy/.env",base_dir));
+    assert_eq!(read_to_string(&compose.env_file).unwrap(),"POSTGRES_TAG=\"16\"\n");
+}
+
+#[test]
+fn test_compose_relative_local_path() {
+    let base_dir: String = temp_dir("compose-relative-local-path");
+    create_dir_all(format!("{}/deploy",base_dir)).unwrap();
+    create_dir_all(format!("{}/local",base_dir)).unwrap();
+    let config_path: String = format!("{}/config.toml",base_dir);
+    std::fs::write(&config_path,format!("{}{}",CONFIG.replace("base_dir = \".\"",&format!("base_dir = \"{}\"",base_dir)),r#"
+[[repositories]]
+service = "db"
+mount_target = "/var/app"
+name = "app"
+path = "local"
+"#)).unwrap();
+    let compose: Compose = Compose::new(Config::read(config_path));
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    assert_eq!(data["services"]["db"]["volumes"][0]["source"],Value::from(format!("{}/local",base_dir)));
+}
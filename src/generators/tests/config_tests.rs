@@ -1,4 +1,27 @@
 use crate::generators::config::Config;
+use crate::generators::tests::helpers::parse_config;
+
+const CONFIG: &str = r#"
+[settings]
+name = "test"
+author = "test"
+description = "test"
+base_dir = "."
+deploy_dir = "deploy"
+services_dir = "services"
+compose_executable = "docker-compose"
+compose_file = "compose.yaml"
+compose_detached = true
+
+[[services]]
+hostname = "db"
+image = "postgres"
+secrets = []
+
+[[env_files]]
+name = "common"
+values = []
+"#;
 
 #[test]
 #[ignore]
@@ -13,4 +36,202 @@ fn test_podman() {
     let config = Config::load("config-test-podman.toml".to_string());
     config.validate();
     assert!(true);
-}
\ No newline at end of file
+}
+
+#[test]
+#[should_panic(expected = "Unable to find secret")]
+fn test_undeclared_secret() {
+    let config = parse_config(&CONFIG.replace("secrets = []","secrets = [\"missing\"]"));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "must declare exactly one source")]
+fn test_secret_with_two_sources() {
+    let config = parse_config(&format!("{}\n[[secrets]]\nname = \"token\"\nfile = \"token.txt\"\nenvironment = \"TOKEN\"\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Invalid healthcheck duration")]
+fn test_invalid_healthcheck_duration() {
+    let config = parse_config(&CONFIG.replace("secrets = []","healthcheck = { test = \"pg_isready\", interval = \"ten seconds\" }"));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "soft value greater than its hard value")]
+fn test_invalid_ulimit() {
+    let config = parse_config(&CONFIG.replace("secrets = []","ulimits = { nofile = { soft = 40000, hard = 20000 } }"));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Unable to find repository")]
+fn test_build_with_undeclared_repository() {
+    let config = parse_config(&CONFIG.replace("secrets = []","build = { repository = \"missing\" }"));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Build context ../secrets of service named")]
+fn test_build_context_outside_repository() {
+    let config = parse_config(&format!("{}\n[[repositories]]\nname = \"app\"\nurl = \"https://example.com/app.git\"\nclone = true\n",CONFIG.replace("secrets = []","build = { repository = \"app\", context = \"../secrets\" }")));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Unable to find service cache")]
+fn test_depends_on_undeclared_service() {
+    let config = parse_config(&CONFIG.replace("secrets = []","depends_on = { cache = { condition = \"service_started\" } }"));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "is declared more than once")]
+fn test_duplicate_service_name() {
+    let config = parse_config(&format!("{}\n[[services]]\nname = \"db\"\nimage = \"mysql\"\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Unable to create a worktree of repository named app without a path")]
+fn test_worktree_without_path() {
+    let config = parse_config(&format!("{}\n[[repositories]]\nname = \"app\"\nbranch = \"main\"\nworktree = true\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "has an invalid relabel Y")]
+fn test_repository_mount_invalid_relabel() {
+    let config = parse_config(&format!("{}\n[[repositories]]\nname = \"app\"\nurl = \"https://example.com/app.git\"\nclone = true\nmounts = [{{ service = \"db\", target = \"/app\", relabel = \"Y\" }}]\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Mount subpath ../../etc of repository named app into service named db must be a relative path without '..'.")]
+fn test_repository_mount_parent_subpath() {
+    let config = parse_config(&format!("{}\n[[repositories]]\nname = \"app\"\nurl = \"https://example.com/app.git\"\nclone = true\nmounts = [{{ service = \"db\", target = \"/app\", subpath = \"../../etc\" }}]\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Mount subpath /etc of repository named app into service named db must be a relative path without '..'.")]
+fn test_repository_absolute_mount_subpath() {
+    let config = parse_config(&format!("{}\n[[repositories]]\nservice = \"db\"\nmount_target = \"/app\"\nmount_subpath = \"/etc\"\nname = \"app\"\nurl = \"https://example.com/app.git\"\nclone = true\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Unable to find service app of repository named app")]
+fn test_repository_undeclared_service() {
+    let config = parse_config(&format!("{}\n[[repositories]]\nservice = \"app\"\nmount_target = \"/app\"\nname = \"app\"\nurl = \"https://example.com/app.git\"\nclone = true\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "must be an absolute container path")]
+fn test_repository_empty_mount_target() {
+    let config = parse_config(&format!("{}\n[[repositories]]\nservice = \"db\"\nname = \"app\"\nurl = \"https://example.com/app.git\"\nclone = true\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Repository app is declared more than once")]
+fn test_duplicate_repository_name() {
+    let repository: &str = "\n[[repositories]]\nname = \"app\"\nurl = \"https://example.com/app.git\"\nclone = true\n";
+    let config = parse_config(&format!("{}{}{}",CONFIG,repository,repository));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Unable to sync repository named app without a branch, tag or rev")]
+fn test_repository_sync_without_reference() {
+    let config = parse_config(&format!("{}\n[[repositories]]\nname = \"app\"\nurl = \"https://example.com/app.git\"\nclone = true\nsync = \"fast_forward\"\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "must be a single directory name")]
+fn test_repository_unsafe_name() {
+    let config = parse_config(&format!("{}\n[[repositories]]\nname = \"../app\"\nurl = \"https://example.com/app.git\"\nclone = true\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Repository name . must be a single directory name")]
+fn test_repository_current_dir_name() {
+    let config = parse_config(&format!("{}\n[[repositories]]\nname = \".\"\nurl = \"https://example.com/app.git\"\nclone = true\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Repository name apps/app must be a single directory name")]
+fn test_repository_nested_name() {
+    let config = parse_config(&format!("{}\n[[repositories]]\nname = \"apps/app\"\nurl = \"https://example.com/app.git\"\nclone = true\n",CONFIG));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "The env file pattern common.env must contain {name} and no '/'.")]
+fn test_invalid_env_file_pattern() {
+    let config = parse_config(&CONFIG.replace("[settings]\n","[settings]\nenv_file_pattern = \"common.env\"\n"));
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "The project env file project_env must use the compose dialect.")]
+fn test_project_env_dialect() {
+    let mut config = parse_config(&format!("{}\n[project_env]\ndialect = \"posix\"\n",CONFIG));
+    config.import_env_files();
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Environment variable PRICE of environment file project_env cannot be written in the Compose dialect")]
+fn test_project_env_unquotable_value() {
+    let mut config = parse_config(&format!("{}\n[project_env]\nvalues = {{ PRICE = 'a\\\\ $5' }}\n",CONFIG));
+    config.import_env_files();
+    config.validate();
+}
+
+#[test]
+#[should_panic(expected = "Unable to find environment file missing")]
+fn test_project_env_undeclared_extends() {
+    let mut config = parse_config(&format!("{}\n[project_env]\nextends = [\"missing\"]\n",CONFIG));
+    config.import_env_files();
+    config.validate();
+}
+
+#[test]
+fn test_config_warnings() {
+    let config = parse_config(&format!("{}{}",CONFIG,r#"
+[[services]]
+name = "app"
+image = "app:${RUSTACK_TEST_UNDEFINED_TAG}"
+command = ["run", "${APP_PORT}", "${RUSTACK_TEST_WITH_DEFAULT:-1}", "$${ESCAPED}"]
+env_file = ["app"]
+environment = { APP_PORT = "9000", LOG_LEVEL = "info" }
+
+[[env_files]]
+name = "app"
+extends = ["base"]
+values = { APP_PORT = "8000", LOG_LEVEL = "info" }
+
+[[env_files]]
+name = "base"
+values = []
+
+[[env_files]]
+name = "unused"
+values = []
+"#));
+    config.validate();
+    let warnings: Vec<String> = config.warnings();
+    assert!(warnings.contains(&String::from("Environment file unused is not used by any service.")));
+    assert!(!warnings.iter().any(|w| w.contains("Environment file base")));
+    assert!(warnings.contains(&String::from("Service named app sets APP_PORT to \"9000\" in environment which overrides \"8000\" of env_file app.")));
+    assert!(!warnings.iter().any(|w| w.contains("LOG_LEVEL")));
+    assert!(warnings.contains(&String::from("Service named app references ${RUSTACK_TEST_UNDEFINED_TAG} but no env file, environment or host variable defines it.")));
+    assert_eq!(warnings.iter().filter(|w| w.contains("references")).count(),1);
+}
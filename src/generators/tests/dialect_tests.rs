@@ -0,0 +1,136 @@
+use crate::generators::prelude::*;
+use crate::generators::dialect::{ is_valid_name, reference_length };
+use crate::generators::tests::helpers::temp_dir;
+
+const VALUES: [&str; 11] = [
+    "plain",
+    "with spaces and # hash",
+    "  leading and trailing spaces  ",
+    "multi\nline\r\nvalue",
+    "double \" and single ' quotes",
+    "back\\slash and `backtick`",
+    "price $5 and $HOME",
+    "it's $5",
+    "a\\b $5",
+    "reference ${DB_HOST} and ${DB_PORT:-5432}",
+    "",
+];
+
+///Renders the values as an environment file of the dialect.
+fn render(dialect: EnvDialect) -> String {
+    let env_file: EnvironmentFile = EnvironmentFile {
+        name: String::from("app"),
+        values: VALUES.iter().enumerate().map(|(index, value)| EnvironmentVar {
+            name: format!("VAR_{}",index),
+            value: value.to_string(),
+            description: String::new(),
+        }).collect(),
+        dialect,
+        extends: Vec::new(),
+        inherit: Vec::new(),
+        source: String::new(),
+        imported: Vec::new(),
+    };
+    env_file.to_env_string().unwrap()
+}
+
+#[test]
+fn test_dialect_round_trip(){
+    for dialect in [EnvDialect::Compose, EnvDialect::Posix, EnvDialect::Systemd] {
+        let variables: Vec<(String,String)> = dialect.parse(&render(dialect)).unwrap();
+        assert_eq!(variables.len(),VALUES.len());
+        for (index, (name, value)) in variables.iter().enumerate() {
+            assert_eq!(name,&format!("VAR_{}",index));
+            assert_eq!(value,VALUES[index],"{:?} value of {}",dialect,name);
+        }
+    }
+}
+
+#[test]
+fn test_dialect_quoting(){
+    assert_eq!(EnvDialect::Compose.quote("a\nb $c ${D}"),"\"a\\nb \\$c ${D}\"");
+    assert_eq!(EnvDialect::Posix.quote("a\nb $c ${D} `e`"),"\"a\nb \\$c ${D} \\`e\\`\"");
+    assert_eq!(EnvDialect::Systemd.quote("${D}"),"\"\\${D}\"");
+    assert_eq!(EnvDialect::Compose.quote("price $5 and $HOME"),"'price $5 and $HOME'");
+    assert_eq!(EnvDialect::Compose.quote("it's $5"),"'it\\'s $5'");
+    assert_eq!(EnvDialect::Compose.quote(""),"");
+}
+
+#[test]
+#[should_panic(expected = "cannot be written in the Compose dialect")]
+fn test_dialect_compose_unquotable(){
+    EnvDialect::Compose.quote("it\\'s $5");
+}
+
+#[test]
+fn test_dialect_posix_shell_source(){
+    let deploy_dir: String = temp_dir("dialect-posix-shell-source");
+    let file_path: String = format!("{}/app.env",deploy_dir);
+    std::fs::write(&file_path,render(EnvDialect::Posix)).unwrap();
+    for (index, value) in VALUES.iter().enumerate().filter(|(_, value)| !value.contains("${")) {
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(format!(". '{}'; printf '%s' \"$VAR_{}\"",file_path,index))
+            .env("HOME","/home/test")
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(),*value);
+    }
+}
+
+#[test]
+fn test_dialect_compose_python_dotenv(){
+    //podman compose reads env files with python-dotenv, skip when it is not installed.
+    let installed: bool = std::process::Command::new("python3").args(["-c","import dotenv"]).status().is_ok_and(|status| status.success());
+    if !installed {
+        eprintln!("python-dotenv is not installed, skipping.");
+        return;
+    }
+    let deploy_dir: String = temp_dir("dialect-compose-python-dotenv");
+    let file_path: String = format!("{}/app.env",deploy_dir);
+    std::fs::write(&file_path,render(EnvDialect::Compose)).unwrap();
+    let output = std::process::Command::new("python3")
+        .arg("-c")
+        .arg("import sys, dotenv; sys.stdout.write('\\0'.join(dotenv.dotenv_values(sys.argv[1]).values()))")
+        .arg(&file_path)
+        .output()
+        .unwrap();
+    let stdout: String = String::from_utf8(output.stdout).unwrap();
+    let values: Vec<&str> = stdout.split('\0').collect();
+    assert_eq!(values.len(),VALUES.len());
+    for (index, value) in VALUES.iter().enumerate().filter(|(_, value)| !value.contains("${")) {
+        assert_eq!(values[index],*value);
+    }
+}
+
+#[test]
+fn test_dialect_parse(){
+    let variables: Vec<(String,String)> = EnvDialect::Compose.parse("# comment\n\nexport A=1\nB = two words # comment\nC='${LITERAL}'\nD=\"x\\ny\"\n").unwrap();
+    assert_eq!(variables,vec![
+        (String::from("A"),String::from("1")),
+        (String::from("B"),String::from("two words")),
+        (String::from("C"),String::from("${LITERAL}")),
+        (String::from("D"),String::from("x\ny")),
+    ]);
+    assert!(EnvDialect::Systemd.parse("; comment\nA=\"unterminated\n").is_err());
+    assert!(EnvDialect::Compose.parse("NOT AN ASSIGNMENT\n").is_err());
+}
+
+#[test]
+fn test_variable_names(){
+    assert!(is_valid_name("DB_HOST"));
+    assert!(is_valid_name("_private1"));
+    assert!(!is_valid_name("1ST"));
+    assert!(!is_valid_name("DB-HOST"));
+    assert!(!is_valid_name("DB HOST"));
+    assert!(!is_valid_name(""));
+    assert_eq!(reference_length("${DB_PORT:-5432} rest"),Some(16));
+    assert_eq!(reference_length("${1}"),None);
+}
+
+#[test]
+#[should_panic(expected = "is not a valid name")]
+fn test_invalid_variable_name(){
+    let env_file: EnvironmentFile = toml::from_str("name = \"app\"\nvalues = [{ name = \"DB-HOST\", value = \"db\" }]\n").unwrap();
+    env_file.validate();
+}
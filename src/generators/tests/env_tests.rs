@@ -1,11 +1,197 @@
 use crate::generators::env::EnvironmentFile;
 use crate::generators::config::Config;
+use crate::generators::prelude::*;
+use crate::generators::tests::helpers::temp_dir;
 
 #[test]
 fn test_env_file(){
     let config = Config::load("config-test-podman.toml".to_string());
     let deploy_dir: String = format!("{}/{}",config.settings.base_dir,&config.settings.deploy_dir);
     config.validate();
-    let env_file_paths: Vec<String> = EnvironmentFile::generate(&config.env_files,&deploy_dir);
+    let env_file_paths: Vec<String> = EnvironmentFile::generate(&config.env_files,&deploy_dir,&config.settings.env_file_layout());
     assert_eq!(env_file_paths.len()>0,true);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_env_file_order_and_descriptions(){
+    let env_file: EnvironmentFile = toml::from_str(r#"
+name = "app"
+values = [
+    { name = "zeta", value = "1", description = "Read last by the app.\nKeep it short." },
+    { name = "alpha", value = "2" },
+    { name = "empty", value = "" },
+]
+"#).unwrap();
+    env_file.validate();
+    let deploy_dir: String = temp_dir("env-file-order");
+    let file_path: String = EnvironmentFile::write(env_file,&deploy_dir,&EnvFileLayout::default()).unwrap();
+    assert_eq!(read_to_string(file_path).unwrap(),"# Read last by the app.\n# Keep it short.\nZETA=\"1\"\nALPHA=\"2\"\nEMPTY=\n");
+}
+
+#[test]
+#[should_panic(expected = "Environment variable myvar is declared more than once in environment file app.")]
+fn test_env_file_duplicate_name(){
+    let env_file: EnvironmentFile = toml::from_str(r#"
+name = "app"
+values = [
+    { name = "MYVAR", value = "1" },
+    { name = "myvar", value = "2" },
+]
+"#).unwrap();
+    env_file.validate();
+}
+
+const EXTENDS_CONFIG: &str = r#"
+[settings]
+name = "test"
+author = "test"
+description = "test"
+base_dir = "/tmp"
+deploy_dir = "deploy"
+services_dir = "services"
+compose_executable = "docker-compose"
+compose_file = "compose.yaml"
+compose_detached = true
+
+[[services]]
+name = "db"
+image = "postgres"
+env_file = ["database", "overrides"]
+environment = { DB_PASSWORD = "inline" }
+
+[[env_files]]
+name = "common"
+values = [
+    { name = "LOG_LEVEL", value = "info" },
+    { name = "DB_HOST", value = "localhost" },
+]
+
+[[env_files]]
+name = "database"
+extends = ["common"]
+values = [
+    { name = "db_host", value = "db" },
+    { name = "DB_PASSWORD", value = "secret" },
+]
+
+[[env_files]]
+name = "overrides"
+values = [
+    { name = "LOG_LEVEL", value = "debug" },
+]
+"#;
+
+#[test]
+fn test_env_file_extends(){
+    let config: Config = toml::from_str(EXTENDS_CONFIG).unwrap();
+    config.validate();
+    let deploy_dir: String = temp_dir("env-file-extends");
+    let file_paths: Vec<String> = EnvironmentFile::generate(&config.env_files,&deploy_dir,&config.settings.env_file_layout());
+    assert_eq!(read_to_string(&file_paths[1]).unwrap(),"LOG_LEVEL=\"info\"\nDB_HOST=\"db\"\nDB_PASSWORD=\"secret\"\n");
+}
+
+#[test]
+fn test_export_environment(){
+    let config: Config = toml::from_str(EXTENDS_CONFIG).unwrap();
+    assert_eq!(config.export_environment("db",ExportFormat::Dotenv),"LOG_LEVEL=\"debug\"\nDB_HOST=\"db\"\nDB_PASSWORD=\"inline\"\n");
+    assert_eq!(config.export_environment("db",ExportFormat::Shell),"export LOG_LEVEL=\"debug\"\nexport DB_HOST=\"db\"\nexport DB_PASSWORD=\"inline\"\n");
+    assert_eq!(config.export_environment("db",ExportFormat::Direnv),config.export_environment("db",ExportFormat::Shell));
+    assert_eq!(config.export_environment("db",ExportFormat::Json),"{\n  \"LOG_LEVEL\": \"debug\",\n  \"DB_HOST\": \"db\",\n  \"DB_PASSWORD\": \"inline\"\n}\n");
+    let config: Config = toml::from_str(&EXTENDS_CONFIG.replace(r#"DB_PASSWORD = "inline""#,r#"DB_PASSWORD = "a \"b\"\n\t$c""#)).unwrap();
+    assert!(config.export_environment("db",ExportFormat::Json).contains(r#""DB_PASSWORD": "a \"b\"\n\t$c""#));
+    assert!(config.export_environment("db",ExportFormat::Shell).contains("export DB_PASSWORD=\"a \\\"b\\\"\n\t\\$c\"\n"));
+    assert_eq!("json".parse::<ExportFormat>(),Ok(ExportFormat::Json));
+    assert!("yaml".parse::<ExportFormat>().is_err());
+}
+
+#[test]
+fn test_effective_environment(){
+    let config: Config = toml::from_str(EXTENDS_CONFIG).unwrap();
+    let variables: Vec<EffectiveVar> = config.effective_environment("db");
+    let file = |file: &str, declared_in: &str| EnvSource::EnvFile { file: file.to_string(), declared_in: declared_in.to_string() };
+    assert_eq!(variables.iter().map(|v| (v.name.as_str(),v.value.as_str(),v.source.clone())).collect::<Vec<_>>(),vec![
+        ("LOG_LEVEL","debug",file("overrides","overrides")),
+        ("DB_HOST","db",file("database","database")),
+        ("DB_PASSWORD","inline",EnvSource::Environment),
+    ]);
+    let config: Config = toml::from_str(&EXTENDS_CONFIG.replace(r#"env_file = ["database", "overrides"]"#,r#"env_file = ["database"]"#)).unwrap();
+    assert_eq!(config.effective_environment("db")[0].to_string(),"LOG_LEVEL=info (env_file database from common)");
+}
+
+#[test]
+#[should_panic(expected = "Environment file common extends itself through common -> database -> common.")]
+fn test_env_file_circular_extends(){
+    let config: Config = toml::from_str(&EXTENDS_CONFIG.replace("name = \"common\"\n","name = \"common\"\nextends = [\"database\"]\n")).unwrap();
+    config.validate();
+}
+
+#[test]
+fn test_env_file_source(){
+    let base_dir: String = temp_dir("env-file-source");
+    std::fs::write(format!("{}/app.env",base_dir),"# imported\nDB_HOST=localhost\nDB_PORT=5432\nGREETING=\"hello \\\"world\\\"\"\n").unwrap();
+    let mut config: Config = toml::from_str(&EXTENDS_CONFIG.replace("base_dir = \"/tmp\"",&format!("base_dir = \"{}\"",base_dir)).replace("name = \"overrides\"\n","name = \"overrides\"\nsource = \"app.env\"\n")).unwrap();
+    config.import_env_files();
+    config.validate();
+    let overrides: EnvironmentFile = config.env_files[2].resolved(&config.env_files);
+    assert_eq!(overrides.values.iter().map(|v| (v.name.as_str(),v.value.as_str())).collect::<Vec<_>>(),vec![
+        ("DB_HOST","localhost"),
+        ("DB_PORT","5432"),
+        ("GREETING","hello \"world\""),
+        ("LOG_LEVEL","debug"),
+    ]);
+    let variables: Vec<EffectiveVar> = config.effective_environment("db");
+    assert_eq!(variables[1].to_string(),format!("DB_HOST=localhost (env_file overrides from {}/app.env)",base_dir));
+
+    let imported: EnvironmentFile = EnvironmentFile::from_env_file("app",&format!("{}/app.env",base_dir),EnvDialect::Compose).unwrap();
+    assert_eq!(imported.to_toml(),"[[env_files]]\nname = \"app\"\nvalues = [\n    { name = \"DB_HOST\", value = \"localhost\" },\n    { name = \"DB_PORT\", value = \"5432\" },\n    { name = \"GREETING\", value = \"hello \\\"world\\\"\" },\n]\n");
+    let parsed: EnvironmentFile = toml::from_str::<Config>(&format!("{}{}",EXTENDS_CONFIG,imported.to_toml())).unwrap().env_files.pop().unwrap();
+    assert_eq!(parsed.values,imported.values);
+}
+
+#[test]
+fn test_env_file_table_values(){
+    let config: Config = toml::from_str(&format!("{}{}",EXTENDS_CONFIG,r#"
+[[env_files]]
+name = "table"
+values = { ZETA = "z", PORT = 5432, DEBUG = true, RATIO = 0.5 }
+"#)).unwrap();
+    config.validate();
+    let env_file: &EnvironmentFile = config.env_files.last().unwrap();
+    assert_eq!(env_file.values.iter().map(|v| (v.name.as_str(),v.value.as_str())).collect::<Vec<_>>(),vec![
+        ("ZETA","z"),
+        ("PORT","5432"),
+        ("DEBUG","true"),
+        ("RATIO","0.5"),
+    ]);
+}
+
+#[test]
+fn test_env_file_table_values_invalid_type(){
+    let error = toml::from_str::<EnvironmentFile>("name = \"table\"\nvalues = { HOSTS = [\"a\", \"b\"] }\n").unwrap_err();
+    assert!(error.to_string().contains("Environment variable HOSTS must be a string, integer, float or boolean, found array."));
+}
+
+#[test]
+fn test_env_file_inherit(){
+    std::env::set_var("RUSTACK_TEST_INHERIT_PROXY","http://proxy:3128");
+    let config: Config = toml::from_str(&EXTENDS_CONFIG.replace("name = \"overrides\"\n",r#"name = "overrides"
+inherit = [
+    "RUSTACK_TEST_INHERIT_PROXY",
+    { name = "RUSTACK_TEST_INHERIT_OPTIONAL", required = false },
+    { name = "RUSTACK_TEST_INHERIT_DEFAULT", default = "1000" },
+]
+"#).replace("environment = { DB_PASSWORD = \"inline\" }","environment = { DB_PASSWORD = \"inline\" }\ninherit = [{ name = \"RUSTACK_TEST_INHERIT_DEFAULT\", default = \"2000\" }]")).unwrap();
+    config.validate();
+    let overrides: EnvironmentFile = config.env_files[2].resolved(&config.env_files);
+    assert_eq!(overrides.to_env_string().unwrap(),"LOG_LEVEL=\"debug\"\nRUSTACK_TEST_INHERIT_PROXY=\"http://proxy:3128\"\nRUSTACK_TEST_INHERIT_DEFAULT=\"1000\"\n");
+    let variables: Vec<EffectiveVar> = config.effective_environment("db");
+    assert_eq!(variables[3].to_string(),"RUSTACK_TEST_INHERIT_PROXY=http://proxy:3128 (env_file overrides from host environment)");
+    assert_eq!(variables[4].to_string(),"RUSTACK_TEST_INHERIT_DEFAULT=2000 (host environment)");
+}
+
+#[test]
+#[should_panic(expected = "Host environment variable RUSTACK_TEST_INHERIT_REQUIRED inherited by environment file overrides is not set.")]
+fn test_env_file_inherit_required(){
+    let config: Config = toml::from_str(&EXTENDS_CONFIG.replace("name = \"overrides\"\n","name = \"overrides\"\ninherit = [\"RUSTACK_TEST_INHERIT_REQUIRED\"]\n")).unwrap();
+    config.validate();
+}
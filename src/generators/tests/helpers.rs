@@ -0,0 +1,80 @@
+use crate::generators::prelude::*;
+use std::fs::create_dir_all;
+use git2::{Repository as GitRepository, RepositoryInitOptions, Signature};
+use std::net::{TcpListener, TcpStream};
+use std::process::{Child, Command, Stdio};
+
+///Creates an empty directory under the system temp directory for a test.
+pub fn temp_dir(name: &str) -> String {
+    let path: PathBuf = std::env::temp_dir().join(format!("rustack-launcher-{}",name));
+    if path.exists() {
+        remove_dir_all(&path).expect("Unable to remove test directory");
+    }
+    create_dir_all(&path).expect("Unable to create test directory");
+    path.to_string_lossy().to_string()
+}
+
+///Parses a configuration from a TOML string.
+pub fn parse_config(data: &str) -> Config {
+    toml::from_str::<Config>(data).expect("Unable to parse test config")
+}
+
+///Creates a local origin repository on branch main with a single commit.
+pub fn init_origin(path: &String) -> GitRepository {
+    let mut options: RepositoryInitOptions = RepositoryInitOptions::new();
+    options.initial_head("main");
+    let repo: GitRepository = GitRepository::init_opts(path,&options).unwrap();
+    commit_file(&repo,"README.md","first");
+    repo
+}
+
+///Writes a file to the repository and commits it.
+pub fn commit_file(repo: &GitRepository, file_name: &str, content: &str) -> String {
+    let workdir: PathBuf = repo.workdir().unwrap().to_path_buf();
+    std::fs::write(workdir.join(file_name),content).unwrap();
+    let mut index = repo.index().unwrap();
+    index.add_path(Path::new(file_name)).unwrap();
+    index.write().unwrap();
+    let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+    let signature: Signature = Signature::now("test","test@example.com").unwrap();
+    let parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit().unwrap()],
+        Err(_) => vec![]
+    };
+    let parents = parents.iter().collect::<Vec<_>>();
+    repo.commit(Some("HEAD"),&signature,&signature,content,&tree,&parents).unwrap().to_string()
+}
+
+///A git daemon serving the repositories of a directory, stopped when dropped.
+pub struct GitDaemon {
+    child: Child,
+    ///The git url of the directory, the name of a repository is appended to it.
+    pub url: String,
+}
+
+impl Drop for GitDaemon {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+///Serves the repositories of the directory over the git protocol on a free port, none if git is not installed.
+pub fn git_daemon(base_path: &String) -> Option<GitDaemon> {
+    let port: u16 = TcpListener::bind("127.0.0.1:0").ok()?.local_addr().ok()?.port();
+    //git daemon runs git-daemon as a child process, run it directly so it is the one stopped.
+    let exec_path: String = String::from_utf8(Command::new("git").arg("--exec-path").output().ok()?.stdout).ok()?;
+    let child: Child = Command::new(format!("{}/git-daemon",exec_path.trim()))
+        .args(["--export-all","--reuseaddr","--listen=127.0.0.1",&format!("--port={}",port),&format!("--base-path={}",base_path)])
+        .stdout(Stdio::null())
+        .spawn()
+        .ok()?;
+    let daemon: GitDaemon = GitDaemon { child, url: format!("git://127.0.0.1:{}",port) };
+    for _ in 0..50 {
+        if TcpStream::connect(("127.0.0.1",port)).is_ok() {
+            return Some(daemon);
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    None
+}
@@ -1,16 +1,15 @@
-use crate::generators::env::EnvironmentFile;
 use crate::generators::compose::Compose;
 use crate::generators::config::Config;
+use crate::generators::launcher::Launcher;
 use std::{thread, time};
 
 #[test]
 fn launch_docker() {
     let config = Config::load("config-test-docker.toml".to_string());
-    let deploy_dir: String = format!("{}/{}",config.settings.base_dir,&config.settings.deploy_dir);
     config.validate();
-    let env_file_paths: Vec<String> = EnvironmentFile::generate(&config.env_files,&deploy_dir);
-    assert_eq!(env_file_paths.len()>0,true);
-    let compose: Compose = Compose::new(config);
+    let mut launcher: Launcher = Launcher::new(config);
+    launcher.fetch_repositories();
+    let compose: Compose = launcher.generate();
     assert!(compose.up());
     thread::sleep(time::Duration::from_secs(5));
     assert!(compose.down());
@@ -18,11 +17,10 @@ fn launch_docker() {
 
 fn launch_podman() {
     let config = Config::load("config-test-podman.toml".to_string());
-    let deploy_dir: String = format!("{}/{}",config.settings.base_dir,&config.settings.deploy_dir);
     config.validate();
-    let env_file_paths: Vec<String> = EnvironmentFile::generate(&config.env_files,&deploy_dir);
-    assert_eq!(env_file_paths.len()>0,true);
-    let compose: Compose = Compose::new(config);
+    let mut launcher: Launcher = Launcher::new(config);
+    launcher.fetch_repositories();
+    let compose: Compose = launcher.generate();
     assert!(compose.up());
     thread::sleep(time::Duration::from_secs(5));
     assert!(compose.down());
@@ -0,0 +1,137 @@
+use crate::generators::prelude::*;
+use crate::generators::tests::helpers::{ temp_dir, parse_config, init_origin };
+use git2::Repository as GitRepository;
+use std::fs::create_dir_all;
+
+const CONFIG: &str = r#"
+[settings]
+name = "test"
+author = "test"
+description = "test"
+base_dir = "BASE_DIR"
+deploy_dir = "deploy"
+services_dir = "services"
+compose_executable = "docker-compose"
+compose_file = "compose.yaml"
+compose_detached = true
+
+[[services]]
+name = "app"
+image = "python"
+env_file = ["common"]
+
+[[env_files]]
+name = "common"
+values = []
+"#;
+
+///Parses the configuration with the base_dir followed by the tables.
+fn launcher_config(base_dir: &String, tables: &str) -> Config {
+    parse_config(&format!("{}{}",CONFIG.replace("BASE_DIR",base_dir),tables))
+}
+
+#[test]
+fn test_launcher_fetch_then_generate() {
+    let base_dir: String = temp_dir("launcher-fetch-then-generate");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    init_origin(&origin_dir);
+    create_dir_all(format!("{}/deploy",base_dir)).unwrap();
+    create_dir_all(format!("{}/services",base_dir)).unwrap();
+    let config: Config = launcher_config(&base_dir,&format!(r#"
+[[repositories]]
+service = "app"
+mount_target = "/app"
+name = "app"
+url = "{origin_dir}"
+branch = "main"
+clone = true
+"#));
+    config.validate();
+    let mut launcher: Launcher = Launcher::new(config);
+    assert!(launcher.repository_paths.is_empty());
+
+    let reports: Vec<RepositorySyncReport> = launcher.fetch_repositories_with_progress(false,&NoProgress);
+    assert_eq!(reports[0].status,SyncStatus::Cloned);
+    assert!(Path::new(&launcher.lock_file()).exists());
+
+    let compose: Compose = launcher.generate();
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    assert_eq!(data["services"]["app"]["volumes"][0]["source"],Value::from(format!("{}/services/app",base_dir)));
+    assert!(Path::new(&format!("{}/deploy/.common.env",base_dir)).exists());
+
+    let launcher: Launcher = Launcher::new(launcher.config);
+    assert_eq!(launcher.repository_paths.get("app"),Some(&format!("{}/services/app",base_dir)));
+}
+
+#[test]
+fn test_launcher_local_path_and_worktree() {
+    let base_dir: String = temp_dir("launcher-local-path-and-worktree");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    let clone_dir: String = format!("{}/clone",base_dir);
+    init_origin(&origin_dir);
+    let origin: GitRepository = GitRepository::open(&origin_dir).unwrap();
+    origin.branch("feature",&origin.head().unwrap().peel_to_commit().unwrap(),false).unwrap();
+    GitRepository::clone(&origin_dir,&clone_dir).unwrap();
+    create_dir_all(format!("{}/deploy",base_dir)).unwrap();
+    create_dir_all(format!("{}/services",base_dir)).unwrap();
+    let mut config: Config = launcher_config(&base_dir,r#"
+[[repositories]]
+service = "app"
+mount_target = "/app"
+name = "local"
+path = "clone"
+
+[[repositories]]
+service = "app"
+mount_target = "/feature"
+name = "feature"
+path = "clone"
+branch = "feature"
+worktree = true
+"#);
+    config.resolve_repository_paths();
+    config.validate();
+    let mut launcher: Launcher = Launcher::new(config);
+    assert_eq!(launcher.repository_paths.get("local"),Some(&clone_dir));
+
+    let reports: Vec<RepositorySyncReport> = launcher.fetch_repositories_with_progress(false,&NoProgress);
+    assert_eq!(reports[0].status,SyncStatus::Local);
+    assert_eq!(reports[1].status,SyncStatus::Cloned);
+    let worktree: GitRepository = GitRepository::open(format!("{}/services/feature",base_dir)).unwrap();
+    assert!(worktree.is_worktree());
+    assert_eq!(worktree.head().unwrap().shorthand(),Some("feature"));
+    let lockfile: Lockfile = Lockfile::load(&launcher.lock_file());
+    assert_eq!(lockfile.repositories.iter().map(|r| r.name.as_str()).collect::<Vec<&str>>(),vec!["feature"]);
+
+    let compose: Compose = launcher.generate();
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    assert_eq!(data["services"]["app"]["volumes"][0]["source"],Value::from(clone_dir));
+    assert_eq!(data["services"]["app"]["volumes"][1]["source"],Value::from(format!("{}/services/feature",base_dir)));
+}
+
+#[test]
+fn test_launcher_project_env() {
+    let base_dir: String = temp_dir("launcher-project-env");
+    create_dir_all(format!("{}/deploy",base_dir)).unwrap();
+    let config: Config = launcher_config(&base_dir,r#"
+[[services]]
+name = "web"
+image = "python:${PYTHON_TAG}"
+
+[[env_files]]
+name = "registry"
+values = [{ name = "registry", value = "localhost" }]
+
+[project_env]
+extends = ["registry"]
+values = { python_tag = "3.12" }
+"#);
+    config.validate();
+    assert!(config.warnings().is_empty());
+    let launcher: Launcher = Launcher::new(config);
+    let compose: Compose = launcher.generate();
+    assert_eq!(compose.env_file,format!("{}/deploy/.env",base_dir));
+    assert_eq!(read_to_string(&compose.env_file).unwrap(),"REGISTRY=\"localhost\"\nPYTHON_TAG=\"3.12\"\n");
+    let data: Value = serde_yaml::from_str(&read_to_string(&compose.file).unwrap()).unwrap();
+    assert_eq!(data["services"]["web"]["image"],Value::from("python:${PYTHON_TAG}"));
+}
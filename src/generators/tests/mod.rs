@@ -1,9 +1,18 @@
+#[cfg(test)]
+pub mod helpers;
+
 #[cfg(test)]
 pub mod config_tests;
 
+#[cfg(test)]
+pub mod compose_tests;
+
 #[cfg(test)]
 pub mod env_tests;
 
+#[cfg(test)]
+pub mod dialect_tests;
+
 
 #[cfg(test)]
 pub mod execute_command_tests;
@@ -11,6 +20,15 @@ pub mod execute_command_tests;
 #[cfg(test)]
 pub mod repository_tests;
 
+#[cfg(test)]
+pub mod auth_tests;
+
+#[cfg(test)]
+pub mod launcher_tests;
+
+#[cfg(test)]
+pub mod progress_tests;
+
 //I dont know yet how to automate this.
 //#[cfg(test)]
 //pub mod launch_tests;
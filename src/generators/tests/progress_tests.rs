@@ -0,0 +1,19 @@
+use crate::generators::prelude::*;
+
+///Returns the transfer progress of a repository with the received objects.
+fn received(received_objects: usize, indexed_objects: usize) -> TransferProgress {
+    TransferProgress { received_objects, indexed_objects, total_objects: 1200, received_bytes: 2048 }
+}
+
+#[test]
+fn test_console_progress_steps(){
+    let progress: ConsoleProgress = ConsoleProgress::default();
+    assert_eq!(progress.progress_line("app",&received(0,0)),None);
+    assert_eq!(progress.progress_line("app",&received(120,0)),Some(String::from("app: received 120/1200 objects (2 KiB)")));
+    assert_eq!(progress.progress_line("app",&received(121,0)),None);
+    assert!(progress.progress_line("db",&received(130,0)).is_some());
+    let lines: usize = (0..400).filter_map(|delta| progress.progress_line("app",&received(1200,delta))).count();
+    assert_eq!(lines,1);
+    progress.started("app");
+    assert!(progress.progress_line("app",&received(1200,1200)).is_some());
+}
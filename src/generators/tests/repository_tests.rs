@@ -1,4 +1,6 @@
-use crate::generators::prelude::{Repository};
+use crate::generators::prelude::*;
+use crate::generators::tests::helpers::{ temp_dir, init_origin, commit_file, git_daemon };
+use git2::Repository as GitRepository;
 
 #[test]
 pub fn test_repository_clone(){
@@ -10,10 +12,279 @@ pub fn test_repository_clone(){
             name: String::from("test"),
             url: "https://github.com/j-p-d-e-v/embedded-rust-led-roulette".to_string(),
             branch: "dev".to_string(),
-            clone: true
+            clone: true,
+            ..Repository::default()
         }
     ]);
     for item in &data {
-        assert_eq!(!Repository::git_clone(&item.name,&item.url,&item.branch,&String::from("services")).len() > 0,true);
+        assert_eq!(!Repository::git_clone(&item.name,&item.url,&item.branch,&String::from("services"),&GitAuth::default()).len() > 0,true);
     }
-}
\ No newline at end of file
+}
+
+fn local_repository(origin: &String, sync: SyncMode) -> Repository {
+    Repository {
+        service: String::new(),
+        mount_target: String::new(),
+        name: String::from("app"),
+        url: origin.clone(),
+        branch: String::from("main"),
+        clone: true,
+        sync,
+        ..Repository::default()
+    }
+}
+
+#[test]
+pub fn test_repository_sync_fast_forward(){
+    let base_dir: String = temp_dir("repository-sync-fast-forward");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    let origin: GitRepository = init_origin(&origin_dir);
+    let repository: Repository = local_repository(&origin_dir,SyncMode::FastForward);
+
+    let report: RepositorySyncReport = repository.sync(&base_dir,&GitAuth::default());
+    assert_eq!(report.status,SyncStatus::Cloned);
+    let report: RepositorySyncReport = repository.sync(&base_dir,&GitAuth::default());
+    assert_eq!(report.status,SyncStatus::UpToDate);
+
+    let old_commit: String = report.new_commit.clone();
+    let new_commit: String = commit_file(&origin,"README.md","second");
+    let report: RepositorySyncReport = repository.sync(&base_dir,&GitAuth::default());
+    assert_eq!(report.status,SyncStatus::Updated);
+    assert_eq!(report.old_commit,old_commit);
+    assert_eq!(report.new_commit,new_commit);
+    assert_eq!(read_to_string(format!("{}/app/README.md",base_dir)).unwrap(),"second");
+}
+
+#[test]
+pub fn test_repository_sync_dirty(){
+    let base_dir: String = temp_dir("repository-sync-dirty");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    let origin: GitRepository = init_origin(&origin_dir);
+    let repository: Repository = local_repository(&origin_dir,SyncMode::Reset);
+    repository.sync(&base_dir,&GitAuth::default());
+
+    commit_file(&origin,"README.md","second");
+    std::fs::write(format!("{}/app/README.md",base_dir),"local change").unwrap();
+    let report: RepositorySyncReport = repository.sync(&base_dir,&GitAuth::default());
+    assert_eq!(report.status,SyncStatus::Dirty);
+    assert_eq!(read_to_string(format!("{}/app/README.md",base_dir)).unwrap(),"local change");
+}
+
+#[test]
+pub fn test_repository_sync_skipped(){
+    let base_dir: String = temp_dir("repository-sync-skipped");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    init_origin(&origin_dir);
+    let repository: Repository = local_repository(&origin_dir,SyncMode::None);
+    repository.sync(&base_dir,&GitAuth::default());
+    assert_eq!(repository.sync(&base_dir,&GitAuth::default()).status,SyncStatus::Skipped);
+}
+
+#[test]
+pub fn test_repository_tag_and_rev(){
+    let base_dir: String = temp_dir("repository-tag-and-rev");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    let origin: GitRepository = init_origin(&origin_dir);
+    let first_commit: String = Repository::head_commit(&origin_dir).unwrap();
+    let tagged_commit: String = commit_file(&origin,"README.md","tagged");
+    let target = origin.revparse_single("HEAD").unwrap();
+    origin.tag_lightweight("v1.0.0",&target,false).unwrap();
+    commit_file(&origin,"README.md","latest");
+
+    let mut repository: Repository = local_repository(&origin_dir,SyncMode::None);
+    repository.tag = String::from("v1.0.0");
+    assert_eq!(repository.reference(),"tag:v1.0.0");
+    assert_eq!(repository.sync(&base_dir,&GitAuth::default()).new_commit,tagged_commit);
+
+    repository.name = String::from("app-rev");
+    repository.tag = String::new();
+    repository.rev = first_commit.clone();
+    assert_eq!(repository.sync(&base_dir,&GitAuth::default()).new_commit,first_commit);
+}
+
+#[test]
+pub fn test_repository_lockfile(){
+    let base_dir: String = temp_dir("repository-lockfile");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    let lock_file: String = format!("{}/rustack.lock",base_dir);
+    let origin: GitRepository = init_origin(&origin_dir);
+    let first_commit: String = Repository::head_commit(&origin_dir).unwrap();
+    let repositories: Vec<Repository> = vec![local_repository(&origin_dir,SyncMode::FastForward)];
+
+    let reports: Vec<RepositorySyncReport> = Repository::sync_all(&repositories,&base_dir,&lock_file,false,&GitAuth::default(),1,&NoProgress);
+    assert_eq!(reports[0].status,SyncStatus::Cloned);
+    assert_eq!(Lockfile::load(&lock_file).repositories[0].commit,first_commit);
+
+    let second_commit: String = commit_file(&origin,"README.md","second");
+    let reports: Vec<RepositorySyncReport> = Repository::sync_all(&repositories,&base_dir,&lock_file,false,&GitAuth::default(),1,&NoProgress);
+    assert_eq!(reports[0].status,SyncStatus::UpToDate);
+    assert_eq!(reports[0].new_commit,first_commit);
+
+    let reports: Vec<RepositorySyncReport> = Repository::sync_all(&repositories,&base_dir,&lock_file,true,&GitAuth::default(),1,&NoProgress);
+    assert_eq!(reports[0].status,SyncStatus::Updated);
+    assert_eq!(Lockfile::load(&lock_file).repositories[0].commit,second_commit);
+
+    std::fs::remove_dir_all(format!("{}/app",base_dir)).unwrap();
+    let reports: Vec<RepositorySyncReport> = Repository::sync_all(&repositories,&base_dir,&lock_file,false,&GitAuth::default(),1,&NoProgress);
+    assert_eq!(reports[0].status,SyncStatus::Cloned);
+    assert_eq!(reports[0].new_commit,second_commit);
+}
+
+#[test]
+pub fn test_repository_update_without_sync(){
+    let base_dir: String = temp_dir("repository-update-without-sync");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    let lock_file: String = format!("{}/rustack.lock",base_dir);
+    let origin: GitRepository = init_origin(&origin_dir);
+    let first_commit: String = Repository::head_commit(&origin_dir).unwrap();
+    let repositories: Vec<Repository> = vec![local_repository(&origin_dir,SyncMode::None)];
+    Repository::sync_all(&repositories,&base_dir,&lock_file,false,&GitAuth::default(),1,&NoProgress);
+
+    let second_commit: String = commit_file(&origin,"README.md","second");
+    let reports: Vec<RepositorySyncReport> = Repository::sync_all(&repositories,&base_dir,&lock_file,false,&GitAuth::default(),1,&NoProgress);
+    assert_eq!(reports[0].new_commit,first_commit);
+
+    let reports: Vec<RepositorySyncReport> = Repository::sync_all(&repositories,&base_dir,&lock_file,true,&GitAuth::default(),1,&NoProgress);
+    assert_eq!(reports[0].status,SyncStatus::Updated);
+    assert_eq!(reports[0].new_commit,second_commit);
+    assert_eq!(Lockfile::load(&lock_file).repositories[0].commit,second_commit);
+}
+
+#[test]
+#[should_panic(expected = "Unable to update repository named app without a branch, tag or rev.")]
+pub fn test_repository_update_without_reference(){
+    let base_dir: String = temp_dir("repository-update-without-reference");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    init_origin(&origin_dir);
+    let repositories: Vec<Repository> = vec![Repository { branch: String::new(), ..local_repository(&origin_dir,SyncMode::None) }];
+    Repository::sync_all(&repositories,&base_dir,&format!("{}/rustack.lock",base_dir),true,&GitAuth::default(),1,&NoProgress);
+}
+
+#[test]
+pub fn test_repository_fetch_commit_by_id(){
+    let base_dir: String = temp_dir("repository-fetch-commit-by-id");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    let origin: GitRepository = init_origin(&origin_dir);
+    origin.config().unwrap().set_bool("uploadpack.allowReachableSHA1InWant",true).unwrap();
+    origin.branch("feature",&origin.head().unwrap().peel_to_commit().unwrap(),false).unwrap();
+    origin.set_head("refs/heads/feature").unwrap();
+    let feature_commit: String = commit_file(&origin,"README.md","feature");
+    let rev_commit: String = commit_file(&origin,"README.md","rev");
+    origin.set_head("refs/heads/main").unwrap();
+    origin.checkout_head(Some(git2::build::CheckoutBuilder::new().force())).unwrap();
+    //The local transport sends every object, fetching a commit by its id needs the git protocol.
+    let Some(daemon) = git_daemon(&base_dir) else {
+        eprintln!("git daemon is not installed, skipping.");
+        return;
+    };
+
+    //The single branch checkout only fetches main, as a shallow one only fetches the last commits.
+    let mut repository: Repository = local_repository(&format!("{}/origin",daemon.url),SyncMode::FastForward);
+    repository.single_branch = true;
+    let report: RepositorySyncReport = repository.sync_locked(&base_dir,Some(&feature_commit),&GitAuth::default(),&NoProgress);
+    assert_eq!(report.status,SyncStatus::Cloned);
+    assert_eq!(report.new_commit,feature_commit);
+
+    repository.rev = rev_commit.clone();
+    assert_eq!(repository.sync(&base_dir,&GitAuth::default()).new_commit,rev_commit);
+}
+
+#[test]
+pub fn test_repository_single_branch_and_sparse_paths(){
+    let base_dir: String = temp_dir("repository-single-branch-and-sparse-paths");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    let origin: GitRepository = init_origin(&origin_dir);
+    std::fs::create_dir_all(format!("{}/services/api",origin_dir)).unwrap();
+    commit_file(&origin,"services/api/main.py","api");
+    let head = origin.head().unwrap().peel_to_commit().unwrap();
+    origin.branch("feature",&head,false).unwrap();
+
+    let mut repository: Repository = local_repository(&origin_dir,SyncMode::FastForward);
+    repository.single_branch = true;
+    repository.sparse_paths = vec![String::from("services/api")];
+    repository.mount_subpath = String::from("services/api/");
+    assert_eq!(repository.sync(&base_dir,&GitAuth::default()).status,SyncStatus::Cloned);
+
+    let clone: GitRepository = GitRepository::open(format!("{}/app",base_dir)).unwrap();
+    assert!(clone.find_reference("refs/remotes/origin/main").is_ok());
+    assert!(clone.find_reference("refs/remotes/origin/feature").is_err());
+    assert!(Path::new(&format!("{}/app/services/api/main.py",base_dir)).exists());
+    assert!(!Path::new(&format!("{}/app/README.md",base_dir)).exists());
+    assert_eq!(repository.mount_source(&format!("{}/app",base_dir)),format!("{}/app/services/api",base_dir));
+    assert_eq!(repository.status(&base_dir,false,&GitAuth::default()).dirty_files,0);
+    std::fs::write(format!("{}/app/services/api/untracked.py",base_dir),"untracked").unwrap();
+    assert_eq!(repository.status(&base_dir,false,&GitAuth::default()).dirty_files,1);
+    std::fs::remove_file(format!("{}/app/services/api/untracked.py",base_dir)).unwrap();
+
+    commit_file(&origin,"services/api/main.py","api v2");
+    assert_eq!(repository.sync(&base_dir,&GitAuth::default()).status,SyncStatus::Updated);
+    assert_eq!(read_to_string(format!("{}/app/services/api/main.py",base_dir)).unwrap(),"api v2");
+    assert!(!Path::new(&format!("{}/app/README.md",base_dir)).exists());
+}
+
+///Records the names of the repositories reported to it.
+#[derive(Default)]
+struct RecordingProgress {
+    started: std::sync::Mutex<Vec<String>>,
+    finished: std::sync::Mutex<Vec<String>>,
+}
+
+impl ProgressReporter for RecordingProgress {
+    fn started(&self, name: &str) {
+        self.started.lock().unwrap().push(name.to_string());
+    }
+
+    fn finished(&self, report: &RepositorySyncReport) {
+        self.finished.lock().unwrap().push(report.name.clone());
+    }
+}
+
+#[test]
+pub fn test_repository_sync_all_concurrently(){
+    let base_dir: String = temp_dir("repository-sync-all-concurrently");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    init_origin(&origin_dir);
+    let repositories: Vec<Repository> = ["one", "two", "three"].iter().map(|name| Repository {
+        name: name.to_string(),
+        ..local_repository(&origin_dir,SyncMode::None)
+    }).collect();
+    let progress: RecordingProgress = RecordingProgress::default();
+    let reports: Vec<RepositorySyncReport> = Repository::sync_all(&repositories,&base_dir,&format!("{}/rustack.lock",base_dir),false,&GitAuth::default(),2,&progress);
+    assert_eq!(reports.iter().map(|r| r.name.as_str()).collect::<Vec<&str>>(),vec!["one","two","three"]);
+    assert!(reports.iter().all(|r| r.status == SyncStatus::Cloned));
+    let mut finished: Vec<String> = progress.finished.lock().unwrap().clone();
+    finished.sort();
+    assert_eq!(finished,vec!["one","three","two"]);
+    assert_eq!(progress.started.lock().unwrap().len(),3);
+}
+
+#[test]
+pub fn test_repository_status(){
+    let base_dir: String = temp_dir("repository-status");
+    let origin_dir: String = format!("{}/origin",base_dir);
+    let origin: GitRepository = init_origin(&origin_dir);
+    let mut repository: Repository = local_repository(&origin_dir,SyncMode::None);
+    assert!(!repository.status(&base_dir,false,&GitAuth::default()).present);
+    repository.sync(&base_dir,&GitAuth::default());
+
+    let clone: GitRepository = GitRepository::open(format!("{}/app",base_dir)).unwrap();
+    let local_commit: String = commit_file(&clone,"local.txt","local");
+    std::fs::write(format!("{}/app/untracked.txt",base_dir),"untracked").unwrap();
+    commit_file(&origin,"README.md","second");
+
+    let status: RepositoryStatus = repository.status(&base_dir,false,&GitAuth::default());
+    assert!(status.present);
+    assert!(status.matches);
+    assert_eq!(status.branch,"main");
+    assert_eq!(status.commit,local_commit);
+    assert_eq!((status.ahead,status.behind,status.dirty_files),(1,0,1));
+
+    let status: RepositoryStatus = repository.status(&base_dir,true,&GitAuth::default());
+    assert_eq!((status.ahead,status.behind),(1,1));
+
+    repository.branch = String::from("develop");
+    let status: RepositoryStatus = repository.status(&base_dir,false,&GitAuth::default());
+    assert!(!status.matches);
+    assert!(status.to_string().ends_with("expected branch:develop"));
+}
+
@@ -1,2 +1,86 @@
+use rustack_launcher::generators::prelude::*;
+
+const USAGE: &str = "Usage:
+    rustack-launcher status <config.toml> [--fetch]    Show the status of the repositories.
+    rustack-launcher update <config.toml>              Update the repositories and refresh the lockfile.
+    rustack-launcher env <config.toml> <service>        Show the effective environment of a service.
+    rustack-launcher export <config.toml> <service> [--format dotenv|shell|json|direnv]
+                                                       Export the effective environment of a service.
+    rustack-launcher import-env <file.env> <name>      Convert an env file to an [[env_files]] table.";
+
 fn main() {
-}
\ No newline at end of file
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("status") => status(&args[1..]),
+        Some("update") => update(&args[1..]),
+        Some("env") => env(&args[1..]),
+        Some("export") => export(&args[1..]),
+        Some("import-env") => import_env(&args[1..]),
+        _ => usage()
+    }
+}
+
+///Prints the usage and exits with an error.
+fn usage() -> ! {
+    eprintln!("{}",USAGE);
+    std::process::exit(2);
+}
+
+///Prints the status of every repository, fetching origin first with --fetch.
+fn status(args: &[String]) {
+    let Some(config_path) = args.iter().find(|arg| !arg.starts_with("--")) else { usage() };
+    let fetch: bool = args.iter().any(|arg| arg == "--fetch");
+    let config: Config = Config::read(config_path.clone());
+    config.validate();
+    let launcher: Launcher = Launcher::new(config);
+    for status in launcher.repository_status(fetch) {
+        println!("{}",status);
+    }
+}
+
+///Updates the repositories to their latest commits and refreshes the lockfile, printing the progress.
+fn update(args: &[String]) {
+    let [config_path] = args else { usage() };
+    let config: Config = Config::read(config_path.clone());
+    config.validate();
+    let mut launcher: Launcher = Launcher::new(config);
+    launcher.update_repositories();
+}
+
+///Prints the effective environment of a service with the source of each value.
+fn env(args: &[String]) {
+    let [config_path, service_name] = args else { usage() };
+    let config: Config = Config::read(config_path.clone());
+    config.validate();
+    for variable in config.effective_environment(service_name) {
+        println!("{}",variable);
+    }
+}
+
+///Prints the effective environment of a service in the format of --format, dotenv by default.
+fn export(args: &[String]) {
+    let (config_path, service_name, format) = match args {
+        [config_path, service_name] => (config_path, service_name, "dotenv"),
+        [config_path, service_name, flag, format] if flag == "--format" => (config_path, service_name, format.as_str()),
+        _ => usage()
+    };
+    let format: ExportFormat = format.parse().unwrap_or_else(|error: String| {
+        eprintln!("{}",error);
+        usage()
+    });
+    let config: Config = Config::read(config_path.clone());
+    config.validate();
+    print!("{}",config.export_environment(service_name,format));
+}
+
+///Prints an existing env file as an [[env_files]] table to paste into the configuration.
+fn import_env(args: &[String]) {
+    let [env_path, name] = args else { usage() };
+    match EnvironmentFile::from_env_file(name,env_path,EnvDialect::Compose) {
+        Ok(env_file) => print!("{}",env_file.to_toml()),
+        Err(error) => {
+            eprintln!("Unable to import {}: {}",env_path,error);
+            std::process::exit(1);
+        }
+    }
+}